@@ -0,0 +1,139 @@
+//! Timings of `BinarySearchTree`, `ArenaSearchTree` and
+//! `BalancedSearchTree` operations on shuffled keys, and the shapes of
+//! the trees they build.
+//!
+//! ```text
+//! cargo bench --bench bst
+//! ```
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use algorithm_exercise::{random, ArenaSearchTree, BalancedSearchTree, BinarySearchTree};
+
+const N: u64 = 200_000;
+const ROUNDS: usize = 5;
+
+/// The operations timed, common to the trees.
+trait Table: Default {
+    fn put(&mut self, key: u64, value: u64);
+    fn get(&self, key: u64) -> Option<u64>;
+    fn delete(&mut self, key: u64);
+}
+
+impl Table for BinarySearchTree<u64, u64> {
+    fn put(&mut self, key: u64, value: u64) {
+        BinarySearchTree::put(self, key, value);
+    }
+    fn get(&self, key: u64) -> Option<u64> {
+        BinarySearchTree::get(self, &key).copied()
+    }
+    fn delete(&mut self, key: u64) {
+        BinarySearchTree::delete(self, &key)
+    }
+}
+
+impl Table for ArenaSearchTree<u64, u64> {
+    fn put(&mut self, key: u64, value: u64) {
+        ArenaSearchTree::put(self, key, value)
+    }
+    fn get(&self, key: u64) -> Option<u64> {
+        ArenaSearchTree::get(self, &key).copied()
+    }
+    fn delete(&mut self, key: u64) {
+        ArenaSearchTree::delete(self, &key)
+    }
+}
+
+impl Table for BalancedSearchTree<u64, u64> {
+    fn put(&mut self, key: u64, value: u64) {
+        BalancedSearchTree::put(self, key, value);
+    }
+    fn get(&self, key: u64) -> Option<u64> {
+        BalancedSearchTree::get(self, &key).copied()
+    }
+    fn delete(&mut self, key: u64) {
+        BalancedSearchTree::delete(self, &key)
+    }
+}
+
+/// Print the best time per key of `ROUNDS` runs of `op`.
+fn report(name: &str, mut op: impl FnMut() -> Duration) {
+    let best = (0..ROUNDS).map(|_| op()).min().unwrap();
+    println!(
+        "{:<16} {:>8.1} ns/key",
+        name,
+        best.as_nanos() as f64 / N as f64
+    );
+}
+
+fn timed(f: impl FnOnce()) -> Duration {
+    let start = Instant::now();
+    f();
+    start.elapsed()
+}
+
+fn filled<T: Table>(keys: &[u64]) -> T {
+    let mut tree = T::default();
+    for &k in keys {
+        tree.put(k, k);
+    }
+    tree
+}
+
+fn lookups<T: Table>(tree: &T, keys: &[u64]) -> Duration {
+    timed(|| {
+        for &k in keys {
+            black_box(tree.get(k));
+        }
+    })
+}
+
+/// Time put (including dropping the tree), get and delete.
+fn bench<T: Table>(name: &str, keys: &[u64], lookup_keys: &[u64]) {
+    report(&format!("{} put", name), || {
+        timed(|| drop(black_box(filled::<T>(keys))))
+    });
+    let tree = filled::<T>(keys);
+    report(&format!("{} get", name), || lookups(&tree, lookup_keys));
+    report(&format!("{} delete", name), || {
+        let mut tree = filled::<T>(keys);
+        timed(|| {
+            for &k in lookup_keys {
+                tree.delete(k);
+            }
+        })
+    });
+}
+
+fn main() {
+    let mut keys: Vec<u64> = (0..N).collect();
+    random::shuffle(&mut keys, &mut random::seeded(1));
+    let mut lookup_keys = keys.clone();
+    random::shuffle(&mut lookup_keys, &mut random::seeded(2));
+
+    bench::<BinarySearchTree<u64, u64>>("bst", &keys, &lookup_keys);
+    bench::<ArenaSearchTree<u64, u64>>("arena", &keys, &lookup_keys);
+    bench::<BalancedSearchTree<u64, u64>>("red-black", &keys, &lookup_keys);
+    let mut tree = filled::<ArenaSearchTree<u64, u64>>(&keys);
+    tree.compact();
+    report("compacted get", || lookups(&tree, &lookup_keys));
+
+    // average depth counting the root as 1, i.e. compares for a search hit
+    let lg = (N as f64).log2();
+    let bst = filled::<BinarySearchTree<u64, u64>>(&keys);
+    println!(
+        "bst shape        height {}, average depth {:.1}, 1.39 lg n = {:.1}",
+        bst.height(),
+        bst.average_depth() + 1.0,
+        1.39 * lg
+    );
+    let rbt = filled::<BalancedSearchTree<u64, u64>>(&keys);
+    println!(
+        "red-black shape  height {}, average depth {:.1}, 2 lg n = {:.1}, black height {}, {} red",
+        rbt.height(),
+        rbt.average_depth() + 1.0,
+        2.0 * lg,
+        rbt.black_height(),
+        rbt.red_count()
+    );
+}
@@ -0,0 +1,130 @@
+/*
+ * C interface of algorithm_exercise, built as a cdylib
+ * (libalgorithm_exercise_ffi.so / .dylib / .dll).
+ *
+ * Generated from src/lib.rs by cbindgen; don't edit. To regenerate run
+ * AE_REGENERATE_HEADER=1 cargo test -p algorithm_exercise_ffi
+ */
+
+#ifndef ALGORITHM_EXERCISE_H
+#define ALGORITHM_EXERCISE_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+/**
+ * `algo` for insertion sort.
+ */
+#define AE_INSERTION_SORT 0
+
+/**
+ * `algo` for selection sort.
+ */
+#define AE_SELECTION_SORT 1
+
+/**
+ * `algo` for bubble sort.
+ */
+#define AE_BUBBLE_SORT 2
+
+/**
+ * `algo` for shellsort.
+ */
+#define AE_SHELLSORT 3
+
+/**
+ * `algo` for quicksort.
+ */
+#define AE_QUICKSORT 4
+
+/**
+ * Returned on success.
+ */
+#define AE_OK 0
+
+/**
+ * Returned for an argument out of range.
+ */
+#define AE_INVALID_ARGUMENT -1
+
+/**
+ * Ordered symbol table from `int64_t` keys to `int64_t` values.
+ */
+typedef struct ae_bst ae_bst;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Sort `len` integers at `keys` in place with algorithm `algo`.
+ * Returns `AE_OK`, or `AE_INVALID_ARGUMENT` for an unknown algorithm or
+ * a null `keys`.
+ *
+ * # Safety
+ * `keys` must point to `len` writable integers.
+ */
+int32_t ae_sort_i64(int64_t *keys, size_t len, int32_t algo);
+
+/**
+ * Sort `len` doubles at `keys` in place with algorithm `algo`, in total
+ * order: `-0.0` before `+0.0` and NaNs last. Returns `AE_OK`, or
+ * `AE_INVALID_ARGUMENT` for an unknown algorithm or a null `keys`.
+ *
+ * # Safety
+ * `keys` must point to `len` writable doubles.
+ */
+int32_t ae_sort_f64(double *keys, size_t len, int32_t algo);
+
+/**
+ * Create an empty tree. Free it with `ae_bst_free`.
+ */
+ae_bst *ae_bst_new(void);
+
+/**
+ * Free a tree created by `ae_bst_new`. Does nothing for null.
+ *
+ * # Safety
+ * `tree` must come from `ae_bst_new` and not be used afterwards.
+ */
+void ae_bst_free(ae_bst *tree);
+
+/**
+ * Insert `key` with `value`, replacing the value if `key` is present.
+ *
+ * # Safety
+ * `tree` must be a live tree from `ae_bst_new`.
+ */
+void ae_bst_put(ae_bst *tree, int64_t key, int64_t value);
+
+/**
+ * Look up `key`. Returns 1 and stores the value in `*value` if found
+ * (and `value` isn't null), else returns 0.
+ *
+ * # Safety
+ * `tree` must be a live tree from `ae_bst_new`, `value` null or
+ * writable.
+ */
+int32_t ae_bst_get(const ae_bst *tree, int64_t key, int64_t *value);
+
+/**
+ * Remove `key` if present.
+ *
+ * # Safety
+ * `tree` must be a live tree from `ae_bst_new`.
+ */
+void ae_bst_delete(ae_bst *tree, int64_t key);
+
+/**
+ * Number of keys in the tree.
+ *
+ * # Safety
+ * `tree` must be a live tree from `ae_bst_new`.
+ */
+size_t ae_bst_size(const ae_bst *tree);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* ALGORITHM_EXERCISE_H */
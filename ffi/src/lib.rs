@@ -0,0 +1,205 @@
+//! C ABI for the sorting algorithms and `BinarySearchTree<i64, i64>`.
+//!
+//! Built as a cdylib of its own so that `algorithm_exercise` itself can be
+//! built without `std`. The declarations for C in
+//! `include/algorithm_exercise.h` are generated from this file by
+//! cbindgen, and the test fails if they're out of date.
+use std::ptr;
+use std::slice;
+
+use algorithm_exercise::total_order::{self, Nans, TotalOrder};
+use algorithm_exercise::{BinarySearchTree, Sorting};
+
+/// `algo` for insertion sort.
+pub const AE_INSERTION_SORT: i32 = 0;
+/// `algo` for selection sort.
+pub const AE_SELECTION_SORT: i32 = 1;
+/// `algo` for bubble sort.
+pub const AE_BUBBLE_SORT: i32 = 2;
+/// `algo` for shellsort.
+pub const AE_SHELLSORT: i32 = 3;
+/// `algo` for quicksort.
+pub const AE_QUICKSORT: i32 = 4;
+
+/// Returned on success.
+pub const AE_OK: i32 = 0;
+/// Returned for an argument out of range.
+pub const AE_INVALID_ARGUMENT: i32 = -1;
+
+/// Ordered symbol table from `int64_t` keys to `int64_t` values.
+pub struct Tree(BinarySearchTree<i64, i64>);
+
+fn algorithm<T: Sorting>(algo: i32) -> Option<fn(&mut [T])> {
+    match algo {
+        AE_INSERTION_SORT => Some(Sorting::insertion_sort),
+        AE_SELECTION_SORT => Some(Sorting::selection_sort),
+        AE_BUBBLE_SORT => Some(Sorting::bubble_sort),
+        AE_SHELLSORT => Some(Sorting::shellsort),
+        AE_QUICKSORT => Some(Sorting::quicksort),
+        _ => None,
+    }
+}
+
+unsafe fn keys<'a, T>(keys: *mut T, len: usize) -> Option<&'a mut [T]> {
+    if len == 0 {
+        Some(&mut [])
+    } else if keys.is_null() {
+        None
+    } else {
+        Some(slice::from_raw_parts_mut(keys, len))
+    }
+}
+
+/// Sort `len` integers at `keys` in place with algorithm `algo`.
+/// Returns `AE_OK`, or `AE_INVALID_ARGUMENT` for an unknown algorithm or
+/// a null `keys`.
+///
+/// # Safety
+/// `keys` must point to `len` writable integers.
+#[no_mangle]
+pub unsafe extern "C" fn ae_sort_i64(keys: *mut i64, len: usize, algo: i32) -> i32 {
+    match (self::keys(keys, len), algorithm(algo)) {
+        (Some(keys), Some(sort)) => {
+            sort(keys);
+            AE_OK
+        }
+        _ => AE_INVALID_ARGUMENT,
+    }
+}
+
+/// Sort `len` doubles at `keys` in place with algorithm `algo`, in total
+/// order: `-0.0` before `+0.0` and NaNs last. Returns `AE_OK`, or
+/// `AE_INVALID_ARGUMENT` for an unknown algorithm or a null `keys`.
+///
+/// # Safety
+/// `keys` must point to `len` writable doubles.
+#[no_mangle]
+pub unsafe extern "C" fn ae_sort_f64(keys: *mut f64, len: usize, algo: i32) -> i32 {
+    match (self::keys(keys, len), algorithm::<TotalOrder<f64>>(algo)) {
+        (Some(keys), Some(sort)) => {
+            total_order::sort_floats(keys, Nans::Last, sort);
+            AE_OK
+        }
+        _ => AE_INVALID_ARGUMENT,
+    }
+}
+
+/// Create an empty tree. Free it with `ae_bst_free`.
+#[no_mangle]
+pub extern "C" fn ae_bst_new() -> *mut Tree {
+    Box::into_raw(Box::new(Tree(BinarySearchTree::new())))
+}
+
+/// Free a tree created by `ae_bst_new`. Does nothing for null.
+///
+/// # Safety
+/// `tree` must come from `ae_bst_new` and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn ae_bst_free(tree: *mut Tree) {
+    if !tree.is_null() {
+        drop(Box::from_raw(tree));
+    }
+}
+
+/// Insert `key` with `value`, replacing the value if `key` is present.
+///
+/// # Safety
+/// `tree` must be a live tree from `ae_bst_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ae_bst_put(tree: *mut Tree, key: i64, value: i64) {
+    (*tree).0.put(key, value);
+}
+
+/// Look up `key`. Returns 1 and stores the value in `*value` if found
+/// (and `value` isn't null), else returns 0.
+///
+/// # Safety
+/// `tree` must be a live tree from `ae_bst_new`, `value` null or
+/// writable.
+#[no_mangle]
+pub unsafe extern "C" fn ae_bst_get(tree: *const Tree, key: i64, value: *mut i64) -> i32 {
+    match (*tree).0.get(&key) {
+        Some(&v) => {
+            if !value.is_null() {
+                ptr::write(value, v);
+            }
+            1
+        }
+        None => 0,
+    }
+}
+
+/// Remove `key` if present.
+///
+/// # Safety
+/// `tree` must be a live tree from `ae_bst_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ae_bst_delete(tree: *mut Tree, key: i64) {
+    (*tree).0.delete(&key);
+}
+
+/// Number of keys in the tree.
+///
+/// # Safety
+/// `tree` must be a live tree from `ae_bst_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ae_bst_size(tree: *const Tree) -> usize {
+    (*tree).0.size()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn test() {
+        unsafe {
+            for algo in AE_INSERTION_SORT..=AE_QUICKSORT {
+                let mut keys = [3i64, -1, 2, 2, 0];
+                assert_eq!(ae_sort_i64(keys.as_mut_ptr(), keys.len(), algo), AE_OK);
+                assert_eq!(keys, [-1, 0, 2, 2, 3]);
+                let mut keys = [1.5, f64::NAN, 0.0, -0.0];
+                assert_eq!(ae_sort_f64(keys.as_mut_ptr(), keys.len(), algo), AE_OK);
+                assert!(keys[0].is_sign_negative() && keys[3].is_nan());
+            }
+            assert_eq!(ae_sort_i64(ptr::null_mut(), 0, AE_QUICKSORT), AE_OK);
+            assert_eq!(
+                ae_sort_i64(ptr::null_mut(), 1, AE_QUICKSORT),
+                AE_INVALID_ARGUMENT
+            );
+            assert_eq!(ae_sort_f64([0.0].as_mut_ptr(), 1, 9), AE_INVALID_ARGUMENT);
+
+            let tree = ae_bst_new();
+            ae_bst_put(tree, 2, 20);
+            ae_bst_put(tree, 1, 10);
+            ae_bst_put(tree, 2, 21);
+            assert_eq!(ae_bst_size(tree), 2);
+            let mut value = 0;
+            assert_eq!(ae_bst_get(tree, 2, &mut value), 1);
+            assert_eq!(value, 21);
+            ae_bst_delete(tree, 2);
+            assert_eq!(ae_bst_get(tree, 2, &mut value), 0);
+            assert_eq!(ae_bst_get(tree, 1, ptr::null_mut()), 1);
+            ae_bst_free(tree);
+            ae_bst_free(ptr::null_mut());
+        }
+
+        // the checked-in header is the one cbindgen generates
+        let dir = env!("CARGO_MANIFEST_DIR");
+        let mut generated = vec![];
+        cbindgen::generate_with_config(dir, cbindgen::Config::from_root_or_default(dir))
+            .expect("can't generate the header")
+            .write(&mut generated);
+        let path = format!("{}/include/algorithm_exercise.h", dir);
+        if env::var_os("AE_REGENERATE_HEADER").is_some() {
+            fs::write(&path, &generated).unwrap();
+        }
+        assert!(
+            fs::read(&path).unwrap() == generated,
+            "{} is out of date, rerun with AE_REGENERATE_HEADER=1",
+            path
+        );
+    }
+}
@@ -0,0 +1,643 @@
+//! Binary search tree with its nodes in one `Vec`.
+//!
+//! Nodes link to each other by `u32` index instead of by pointer, so
+//! building the tree allocates only when the `Vec` grows and dropping it
+//! frees one buffer. Slots of deleted nodes go on a free list for reuse;
+//! `compact` drops them and lays the nodes out in key order.
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::fmt;
+use core::mem;
+
+/// Index of no node.
+const NIL: u32 = u32::MAX;
+
+pub struct ArenaSearchTree<T, U> {
+    nodes: Vec<Slot<T, U>>,
+    root: u32,
+    /// Head of the list of free slots, linked through `Slot::Free`.
+    free: u32,
+}
+
+enum Slot<T, U> {
+    Node(Node<T, U>),
+    Free(u32),
+}
+
+struct Node<T, U> {
+    key: T,
+    value: U,
+    left: u32,
+    right: u32,
+    n: u32,
+}
+
+impl<T: Ord, U> ArenaSearchTree<T, U> {
+    /// Initializer.
+    pub fn new() -> Self {
+        ArenaSearchTree {
+            nodes: vec![],
+            root: NIL,
+            free: NIL,
+        }
+    }
+
+    /// An empty tree with room for `capacity` nodes.
+    pub fn with_capacity(capacity: usize) -> Self {
+        ArenaSearchTree {
+            nodes: Vec::with_capacity(capacity),
+            root: NIL,
+            free: NIL,
+        }
+    }
+
+    fn node(&self, i: u32) -> &Node<T, U> {
+        match self.nodes[i as usize] {
+            Slot::Node(ref node) => node,
+            Slot::Free(_) => unreachable!("link to a free slot"),
+        }
+    }
+
+    fn node_mut(&mut self, i: u32) -> &mut Node<T, U> {
+        match self.nodes[i as usize] {
+            Slot::Node(ref mut node) => node,
+            Slot::Free(_) => unreachable!("link to a free slot"),
+        }
+    }
+
+    fn node_size(&self, i: u32) -> usize {
+        if i == NIL {
+            0
+        } else {
+            self.node(i).n as usize
+        }
+    }
+
+    fn update_size(&mut self, i: u32) {
+        let n = self.node_size(self.node(i).left) + self.node_size(self.node(i).right) + 1;
+        self.node_mut(i).n = n as u32;
+    }
+
+    /// Store `node` in a free slot, or a new one, and return its index.
+    fn allocate(&mut self, node: Node<T, U>) -> u32 {
+        if self.free == NIL {
+            let i = self.nodes.len();
+            assert!(i < NIL as usize, "ArenaSearchTree is full");
+            self.nodes.push(Slot::Node(node));
+            i as u32
+        } else {
+            let i = self.free;
+            self.free = match mem::replace(&mut self.nodes[i as usize], Slot::Node(node)) {
+                Slot::Free(next) => next,
+                Slot::Node(_) => unreachable!("free list links to a node"),
+            };
+            i
+        }
+    }
+
+    /// Put slot `i` on the free list, dropping its node.
+    fn release(&mut self, i: u32) {
+        self.nodes[i as usize] = Slot::Free(self.free);
+        self.free = i;
+    }
+
+    /// The link from `parent` to its child on the `side` of it, or the
+    /// root link if `parent` is `NIL`.
+    fn link_mut(&mut self, parent: u32, side: Ordering) -> &mut u32 {
+        if parent == NIL {
+            return &mut self.root;
+        }
+        let node = self.node_mut(parent);
+        if side == Ordering::Less {
+            &mut node.left
+        } else {
+            &mut node.right
+        }
+    }
+
+    /// Add one to (`grow`) or take one from the counts of the nodes on
+    /// the search path of `key`, stopping above its node. Undoes the counts
+    /// of a `put` or `delete` that doesn't change the tree after all.
+    fn recount_path<Q: Ord + ?Sized>(&mut self, key: &Q, grow: bool)
+    where
+        T: Borrow<Q>,
+    {
+        let mut i = self.root;
+        while i != NIL {
+            let node = self.node_mut(i);
+            i = match key.cmp(node.key.borrow()) {
+                Ordering::Less => node.left,
+                Ordering::Greater => node.right,
+                Ordering::Equal => return,
+            };
+            if grow {
+                node.n += 1;
+            } else {
+                node.n -= 1;
+            }
+        }
+    }
+
+    /// Return size of the tree, ie. count of nodes.
+    pub fn size(&self) -> usize {
+        self.node_size(self.root)
+    }
+
+    /// Whether the tree has no keys.
+    pub fn is_empty(&self) -> bool {
+        self.root == NIL
+    }
+
+    /// Whether the tree has key `key`.
+    pub fn contains<Q: Ord + ?Sized>(&self, key: &Q) -> bool
+    where
+        T: Borrow<Q>,
+    {
+        self.find(key) != NIL
+    }
+
+    fn find<Q: Ord + ?Sized>(&self, key: &Q) -> u32
+    where
+        T: Borrow<Q>,
+    {
+        let mut i = self.root;
+        while i != NIL {
+            let node = self.node(i);
+            match key.cmp(node.key.borrow()) {
+                Ordering::Less => i = node.left,
+                Ordering::Greater => i = node.right,
+                Ordering::Equal => break,
+            }
+        }
+        i
+    }
+
+    /// Get the value for `key`. Returns `None` if the tree doesn't have key `key`.
+    /// ```
+    /// # use algorithm_exercise::*;
+    /// let mut tree = ArenaSearchTree::new();
+    /// tree.put("one", 1);
+    /// tree.put("two", 2);
+    /// assert_eq!(tree.get("two"), Some(&2));
+    /// assert_eq!(tree.get(""), None);
+    /// ```
+    pub fn get<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&U>
+    where
+        T: Borrow<Q>,
+    {
+        match self.find(key) {
+            NIL => None,
+            i => Some(&self.node(i).value),
+        }
+    }
+
+    /// Insert key value pair into tree. If key already exists, update the corresponding value.
+    pub fn put(&mut self, key: T, val: U) {
+        // count the new node in on the way down, taking it back if the key
+        // turns out to be there already
+        let (mut parent, mut side) = (NIL, Ordering::Equal);
+        let mut i = self.root;
+        while i != NIL {
+            let node = self.node_mut(i);
+            side = key.cmp(&node.key);
+            let next = match side {
+                Ordering::Less => node.left,
+                Ordering::Greater => node.right,
+                Ordering::Equal => {
+                    node.value = val;
+                    self.recount_path(&key, false);
+                    return;
+                }
+            };
+            node.n += 1;
+            parent = i;
+            i = next;
+        }
+        let i = self.allocate(Node {
+            key,
+            value: val,
+            left: NIL,
+            right: NIL,
+            n: 1,
+        });
+        *self.link_mut(parent, side) = i;
+    }
+
+    /// Get the minimal key.
+    pub fn min(&self) -> Option<&T> {
+        match self.root {
+            NIL => None,
+            i => Some(&self.node(self.min_internal(i)).key),
+        }
+    }
+
+    fn min_internal(&self, mut i: u32) -> u32 {
+        while self.node(i).left != NIL {
+            i = self.node(i).left;
+        }
+        i
+    }
+
+    /// Get the maximum key.
+    pub fn max(&self) -> Option<&T> {
+        match self.root {
+            NIL => None,
+            i => Some(&self.node(self.max_internal(i)).key),
+        }
+    }
+
+    fn max_internal(&self, mut i: u32) -> u32 {
+        while self.node(i).right != NIL {
+            i = self.node(i).right;
+        }
+        i
+    }
+
+    /// Get the largest key in the tree less than or equal to `key`.
+    pub fn floor<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+    {
+        let mut floor = None;
+        let mut i = self.root;
+        while i != NIL {
+            let node = self.node(i);
+            match key.cmp(node.key.borrow()) {
+                Ordering::Less => i = node.left,
+                Ordering::Greater => {
+                    floor = Some(&node.key);
+                    i = node.right;
+                }
+                Ordering::Equal => return Some(&node.key),
+            }
+        }
+        floor
+    }
+
+    /// Get the smallest key in the tree greater than or equal to `key`.
+    pub fn ceiling<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+    {
+        self.key_of_rank(self.rank_of_key(key))
+    }
+
+    /// Get the key of rank `rank`, i.e. the key with precisely `rank`
+    /// smaller keys in the tree.
+    pub fn key_of_rank(&self, mut rank: usize) -> Option<&T> {
+        let mut i = self.root;
+        while i != NIL {
+            let node = self.node(i);
+            let t = self.node_size(node.left);
+            if t > rank {
+                i = node.left;
+            } else if t < rank {
+                rank -= t + 1;
+                i = node.right;
+            } else {
+                return Some(&node.key);
+            }
+        }
+        None
+    }
+
+    /// Inverse method of `key_of_rank()`.
+    pub fn rank_of_key<Q: Ord + ?Sized>(&self, key: &Q) -> usize
+    where
+        T: Borrow<Q>,
+    {
+        let mut rank = 0;
+        let mut i = self.root;
+        while i != NIL {
+            let node = self.node(i);
+            match key.cmp(node.key.borrow()) {
+                Ordering::Less => i = node.left,
+                Ordering::Greater => {
+                    rank += 1 + self.node_size(node.left);
+                    i = node.right;
+                }
+                Ordering::Equal => return rank + self.node_size(node.left),
+            }
+        }
+        rank
+    }
+
+    /// Delete the node with minimal value.
+    pub fn delete_min(&mut self) {
+        if self.root != NIL {
+            let min = self.min_internal(self.root);
+            self.root = self.delete_min_internal(self.root);
+            self.release(min);
+        }
+    }
+
+    /// Unlink the node with the minimal key below `root`, returning the
+    /// new root of the subtree. The node stays allocated.
+    fn delete_min_internal(&mut self, root: u32) -> u32 {
+        let (mut parent, mut i) = (NIL, root);
+        while self.node(i).left != NIL {
+            let node = self.node_mut(i);
+            node.n -= 1;
+            parent = i;
+            i = node.left;
+        }
+        let right = self.node(i).right;
+        if parent == NIL {
+            return right;
+        }
+        self.node_mut(parent).left = right;
+        root
+    }
+
+    /// Delete the node with maximal value.
+    pub fn delete_max(&mut self) {
+        if self.root != NIL {
+            let max = self.max_internal(self.root);
+            self.root = self.delete_max_internal(self.root);
+            self.release(max);
+        }
+    }
+
+    /// Unlink the node with the maximal key below `root`, returning the
+    /// new root of the subtree. The node stays allocated.
+    fn delete_max_internal(&mut self, root: u32) -> u32 {
+        let (mut parent, mut i) = (NIL, root);
+        while self.node(i).right != NIL {
+            let node = self.node_mut(i);
+            node.n -= 1;
+            parent = i;
+            i = node.right;
+        }
+        let left = self.node(i).left;
+        if parent == NIL {
+            return left;
+        }
+        self.node_mut(parent).right = left;
+        root
+    }
+
+    /// Delete the node of `key`.
+    pub fn delete<Q: Ord + ?Sized>(&mut self, key: &Q)
+    where
+        T: Borrow<Q>,
+    {
+        // count the node out on the way down, taking that back if the key
+        // turns out to be missing
+        let (mut parent, mut side) = (NIL, Ordering::Equal);
+        let mut i = self.root;
+        loop {
+            if i == NIL {
+                self.recount_path(key, true);
+                return;
+            }
+            let node = self.node_mut(i);
+            let ordering = key.cmp(node.key.borrow());
+            let next = match ordering {
+                Ordering::Less => node.left,
+                Ordering::Greater => node.right,
+                Ordering::Equal => break,
+            };
+            node.n -= 1;
+            parent = i;
+            side = ordering;
+            i = next;
+        }
+        let node = self.node(i);
+        let (left, right) = (node.left, node.right);
+        self.release(i);
+        let replacement = if left == NIL {
+            right
+        } else if right == NIL {
+            left
+        } else {
+            // replace the node by its successor, the minimum of the right subtree
+            let successor = self.min_internal(right);
+            let right = self.delete_min_internal(right);
+            let node = self.node_mut(successor);
+            node.left = left;
+            node.right = right;
+            self.update_size(successor);
+            successor
+        };
+        *self.link_mut(parent, side) = replacement;
+    }
+
+    /// Drop the free slots and store the nodes in order of keys, so that
+    /// iterating and searching walk memory front to back.
+    /// ```
+    /// # use algorithm_exercise::*;
+    /// let mut tree = ArenaSearchTree::new();
+    /// for k in 0..100 {
+    ///     tree.put(k * 7 % 100, k);
+    /// }
+    /// tree.delete_min();
+    /// tree.compact();
+    /// assert_eq!(tree.size(), 99);
+    /// assert_eq!(tree.min(), Some(&1));
+    /// ```
+    pub fn compact(&mut self) {
+        let order: Vec<u32> = self.indices().collect();
+        let mut new_index = vec![NIL; self.nodes.len()];
+        for (rank, &i) in order.iter().enumerate() {
+            new_index[i as usize] = rank as u32;
+        }
+        let relink = |i: u32| if i == NIL { NIL } else { new_index[i as usize] };
+        let mut old = mem::replace(&mut self.nodes, Vec::with_capacity(order.len()));
+        for &i in &order {
+            match mem::replace(&mut old[i as usize], Slot::Free(NIL)) {
+                Slot::Node(mut node) => {
+                    node.left = relink(node.left);
+                    node.right = relink(node.right);
+                    self.nodes.push(Slot::Node(node));
+                }
+                Slot::Free(_) => unreachable!("link to a free slot"),
+            }
+        }
+        self.root = relink(self.root);
+        self.free = NIL;
+    }
+
+    /// Node indices in order of keys.
+    fn indices(&self) -> Indices<'_, T, U> {
+        let mut indices = Indices {
+            tree: self,
+            stack: vec![],
+        };
+        indices.push_left(self.root);
+        indices
+    }
+
+    /// Iterate over the key value pairs in order of keys.
+    /// ```
+    /// # use algorithm_exercise::*;
+    /// let mut tree = ArenaSearchTree::new();
+    /// tree.put(2, "two");
+    /// tree.put(1, "one");
+    /// assert_eq!(tree.iter().collect::<Vec<_>>(), [(&1, &"one"), (&2, &"two")]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (&T, &U)> + '_ {
+        self.indices().map(move |i| {
+            let node = self.node(i);
+            (&node.key, &node.value)
+        })
+    }
+
+    /// Iterate over the keys in order.
+    pub fn keys(&self) -> impl Iterator<Item = &T> + '_ {
+        self.indices().map(move |i| &self.node(i).key)
+    }
+}
+
+impl<T: Ord, U> Default for ArenaSearchTree<T, U> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord + fmt::Debug, U: fmt::Debug> fmt::Debug for ArenaSearchTree<T, U> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// In-order walk over the node indices of an `ArenaSearchTree`.
+struct Indices<'a, T, U> {
+    tree: &'a ArenaSearchTree<T, U>,
+    /// Nodes to yield, each before its right subtree.
+    stack: Vec<u32>,
+}
+
+impl<T: Ord, U> Indices<'_, T, U> {
+    fn push_left(&mut self, mut i: u32) {
+        while i != NIL {
+            self.stack.push(i);
+            i = self.tree.node(i).left;
+        }
+    }
+}
+
+impl<T: Ord, U> Iterator for Indices<'_, T, U> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        let i = self.stack.pop()?;
+        self.push_left(self.tree.node(i).right);
+        Some(i)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symbol_table::tests::{random_changes, Changes};
+
+    /// The arena's removals don't return what they remove, so look first.
+    impl Changes for ArenaSearchTree<u32, u32> {
+        fn put(&mut self, key: u32, value: u32) -> Option<u32> {
+            let previous = self.get(&key).cloned();
+            ArenaSearchTree::put(self, key, value);
+            previous
+        }
+        fn pop_min(&mut self) -> Option<(u32, u32)> {
+            let entry = self
+                .min()
+                .map(|&min| (min, self.get(&min).cloned().unwrap()));
+            self.delete_min();
+            entry
+        }
+        fn pop_max(&mut self) -> Option<(u32, u32)> {
+            let entry = self
+                .max()
+                .map(|&max| (max, self.get(&max).cloned().unwrap()));
+            self.delete_max();
+            entry
+        }
+        fn remove(&mut self, key: &u32) -> Option<(u32, u32)> {
+            let entry = self.get(key).map(|&value| (*key, value));
+            self.delete(key);
+            entry
+        }
+    }
+
+    #[test]
+    fn test() {
+        let mut table = ArenaSearchTree::new();
+        table.put(3, "3");
+        table.put(4, "4");
+        table.put(2, "2");
+        assert_eq!(table.size(), 3);
+        assert_eq!(table.get(&3), Some(&"3"));
+        assert_eq!(table.floor(&5), Some(&4));
+        assert_eq!(table.ceiling(&3), Some(&3));
+        table.delete(&3);
+        // the freed slot is reused
+        table.put(5, "5");
+        assert_eq!(table.nodes.len(), 3);
+        assert_eq!(table.keys().collect::<Vec<_>>(), [&2, &4, &5]);
+
+        // random changes, compacting now and then
+        let mut table = ArenaSearchTree::with_capacity(100);
+        let mut round = 0;
+        let model = random_changes(&mut table, 4, 3000, 100, |table, model| {
+            if round % 500 == 0 {
+                table.compact();
+                assert_eq!(table.nodes.len(), table.size());
+            }
+            round += 1;
+            assert_eq!(table.size(), model.len());
+            assert_eq!(
+                (table.min(), table.max()),
+                (model.keys().next(), model.keys().next_back())
+            );
+            for q in (0..100).step_by(9) {
+                assert_eq!(
+                    table.floor(&q),
+                    model.range(..=q).next_back().map(|(k, _)| k)
+                );
+                assert_eq!(table.rank_of_key(&q), model.range(..q).count());
+                assert_eq!(table.key_of_rank(q as usize), model.keys().nth(q as usize));
+            }
+        });
+        assert!(table.iter().eq(model.iter()));
+        table.compact();
+        assert!(table.iter().eq(model.iter()));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn degenerate() {
+        // sorted keys build a tree as tall as it is big, and a stack far
+        // too small to recurse down it
+        std::thread::Builder::new()
+            .stack_size(64 * 1024)
+            .spawn(|| {
+                let n = 5000;
+                let mut table = ArenaSearchTree::new();
+                for k in 0..n {
+                    table.put(k, k);
+                }
+                table.put(n - 1, 0);
+                assert_eq!(table.size(), n as usize);
+                assert_eq!(table.get(&(n - 1)), Some(&0));
+                assert_eq!(table.floor(&n), Some(&(n - 1)));
+                assert_eq!(table.rank_of_key(&(n - 1)), n as usize - 1);
+                table.delete(&(n - 2));
+                table.delete(&n);
+                table.delete_max();
+                table.delete_min();
+                assert_eq!(table.size(), n as usize - 3);
+                assert_eq!((table.min(), table.max()), (Some(&1), Some(&(n - 3))));
+                table.compact();
+                assert!(table.keys().cloned().eq(1..n - 2));
+                for k in (1..n - 2).rev() {
+                    table.delete(&k);
+                }
+                assert!(table.is_empty());
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+}
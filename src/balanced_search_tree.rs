@@ -1,155 +1,1017 @@
-use std::cell::RefCell;
-use std::rc::Rc;
-static RED: bool = true;
-static BLACK: bool = false;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::fmt;
+use core::mem;
+use core::ops::{Bound, RangeBounds};
 
-struct Node<K: PartialOrd + Copy, V: Clone> {
+use crate::invariants::{Report, Violation};
+use crate::render::{self, Drawn};
+use crate::symbol_table::OrderedSymbolTable;
+use crate::tree::{self, TreeNode};
+
+const RED: bool = true;
+const BLACK: bool = false;
+
+type Link<K, V> = Option<Box<Node<K, V>>>;
+
+/// Ordered symbol table kept as a left-leaning red-black tree, the binary
+/// form of a 2-3 tree: a red link joins two keys of the same 3-node. The
+/// tree stays balanced, so lookups and insertions take logarithmic time
+/// whatever order the keys arrive in. The API follows `BinarySearchTree`.
+/// ```
+/// # use algorithm_exercise::*;
+/// let mut tree = BalancedSearchTree::new();
+/// for k in 0..1000 {
+///     tree.put(k, k * k);
+/// }
+/// assert_eq!(tree.get(&30), Some(&900));
+/// assert_eq!(tree.rank_of_key(&500), 500);
+/// ```
+pub struct BalancedSearchTree<K, V> {
+    root: Link<K, V>,
+}
+
+/// Red-black tree node, `color` being that of the link from its parent.
+struct Node<K, V> {
     key: K,
     value: V,
-    left: Rc<RefCell<Option<Node<K,V>>>>,
-    right: Rc<RefCell<Option<Node<K,V>>>>,
+    left: Link<K, V>,
+    right: Link<K, V>,
     n: usize,
     color: bool,
 }
 
-pub struct BalancedSearchTree<K: PartialOrd + Copy, V: Clone> {
-    root: Rc<RefCell<Option<Node<K, V>>>>,
-}
-
-impl<K: PartialOrd + Copy, V: Clone> Node<K, V> {
+impl<K: Ord, V> Node<K, V> {
     fn new(key: K, value: V, n: usize, color: bool) -> Self {
         Node {
             key,
             value,
             n,
             color,
-            left: Rc::new(RefCell::new(None)),
-            right: Rc::new(RefCell::new(None)),
+            left: None,
+            right: None,
         }
     }
 }
 
-impl<K: PartialOrd + Copy, V: Clone> BalancedSearchTree<K, V> {
+impl<K, V> TreeNode for Node<K, V> {
+    type Key = K;
+    type Value = V;
+
+    fn key(&self) -> &K {
+        &self.key
+    }
+
+    fn value(&self) -> &V {
+        &self.value
+    }
+
+    fn left(&self) -> Option<&Self> {
+        self.left.as_deref()
+    }
+
+    fn right(&self) -> Option<&Self> {
+        self.right.as_deref()
+    }
+
+    fn size(&self) -> usize {
+        self.n
+    }
+}
+
+impl<K: Ord, V> BalancedSearchTree<K, V> {
+    /// Initializer.
     pub fn new() -> Self {
-        BalancedSearchTree { root: Rc::new(RefCell::new(None)) }
-    }
-
-    fn is_red(node: &Rc<RefCell<Option<Node<K, V>>>>) -> bool {
-        match *node.borrow_mut() {
-            Some(ref node) => node.color == RED,
-            None => false,
-        }
-    }
-
-    fn rotate_left(
-        node: &Rc<RefCell<Option<Node<K, V>>>>,
-    ) -> Rc<RefCell<Option<Node<K, V>>>> {
-        match *node.borrow_mut() {
-            Some(ref mut h) => {
-                let x = h.right.clone();
-                h.right = match *x.borrow() {
-                    None => Rc::new(RefCell::new(None)),
-                    Some(ref x) => x.left.clone()
-                };
-                if let Some(ref mut x) = *x.borrow_mut() {
-                    x.color = h.color;
-                    h.color = RED;
-                    x.n = h.n;
-                    h.n = 1 + Self::size(&h.left) + Self::size(&h.right);
-                    x.left = node.clone();
-                }
-                x
-            },
-            None => node.clone()
-        }
-       
-    }
-
-    fn rotate_right(
-        node: &Rc<RefCell<Option<Node<K, V>>>>,
-    ) -> Rc<RefCell<Option<Node<K, V>>>> {
-        match *node.borrow_mut() {
-            Some(ref mut h) => {
-                let x = h.left.clone();
-                h.left = match *x.borrow() {
-                    None => Rc::new(RefCell::new(None)),
-                    Some(ref x) => x.right.clone()
-                };
-                if let Some(ref mut x) = *x.borrow_mut() {
-                    x.color = h.color;
-                    h.color = RED;
-                    x.n = h.n;
-                    h.n = 1 + Self::size(&h.left) + Self::size(&h.right);
-                    x.right = node.clone();
-                }
-                x
-            },
-            None => node.clone()
-        }
-       
+        BalancedSearchTree { root: None }
     }
 
-    fn size(rc_node: &Rc<RefCell<Option<Node<K, V>>>>) -> usize {
-        match *rc_node.borrow() {
-            Some(ref x) => x.n,
-            None => 0,
-        }
+    /// Number of keys in the tree.
+    pub fn size(&self) -> usize {
+        Self::node_size(&self.root)
     }
 
-    fn flip_color(node: &Rc<RefCell<Option<Node<K, V>>>> ) {
-        match *node.borrow_mut() {
-            Some(ref mut node) => {
-                node.color = RED;
-                if let Some(ref mut left) = *node.left.borrow_mut() {
-                    left.color = BLACK;
-                }
-                if let Some(ref mut right) = *node.right.borrow_mut() {
-                    right.color = BLACK;
-                }
-            },
-            None => ()
+    /// Whether the tree has no keys.
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Whether the tree has key `key`.
+    pub fn contains<Q: Ord + ?Sized>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.get(key).is_some()
+    }
+
+    /// Get the value for `key`. Returns `None` if the tree doesn't have key `key`.
+    pub fn get<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+    {
+        let mut node = &self.root;
+        while let Some(ref h) = *node {
+            node = match key.cmp(h.key.borrow()) {
+                Ordering::Less => &h.left,
+                Ordering::Greater => &h.right,
+                Ordering::Equal => return Some(&h.value),
+            };
         }
+        None
     }
 
-    pub fn put(&mut self, key: K, value: V) {
-        self.root = Self::put_internal(self.root.clone(), key, value);
-        match *self.root.borrow_mut(){
-            Some(ref mut h) => h.color = BLACK,
-            None => unreachable!()
+    /// Get a mutable reference to the value for `key`.
+    pub fn get_mut<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+    {
+        let mut node = &mut self.root;
+        while let Some(ref mut h) = *node {
+            node = match key.cmp(h.key.borrow()) {
+                Ordering::Less => &mut h.left,
+                Ordering::Greater => &mut h.right,
+                Ordering::Equal => return Some(&mut h.value),
+            };
         }
+        None
+    }
+
+    /// Insert key value pair into tree. If key already exists, update the corresponding value.
+    /// Returns the previous value, or `None` if the key is new.
+    /// ```
+    /// # use algorithm_exercise::*;
+    /// let mut tree = BalancedSearchTree::new();
+    /// assert_eq!(tree.put(1, "one"), None);
+    /// assert_eq!(tree.put(1, "uno"), Some("one"));
+    /// ```
+    pub fn put(&mut self, key: K, value: V) -> Option<V> {
+        let mut previous = None;
+        let mut root = Self::put_internal(self.root.take(), key, value, &mut previous);
+        root.color = BLACK;
+        self.root = Some(root);
+        previous
     }
 
     fn put_internal(
-        node: Rc<RefCell<Option<Node<K, V>>>>,
+        node: Link<K, V>,
         key: K,
         value: V,
-    ) -> Rc<RefCell<Option<Node<K, V>>>> {
-        match *node.borrow_mut() {
-            Some(ref mut h) => {
-                if key < h.key {
-                    h.left = Self::put_internal(h.left.clone(), key, value)
-                } else if key > h.key {
-                    h.right = Self::put_internal(h.right.clone(), key, value)
-                } else {
-                    h.value = value
+        previous: &mut Option<V>,
+    ) -> Box<Node<K, V>> {
+        let mut h = match node {
+            Some(h) => h,
+            None => return Box::new(Node::new(key, value, 1, RED)),
+        };
+        match key.cmp(&h.key) {
+            Ordering::Less => {
+                h.left = Some(Self::put_internal(h.left.take(), key, value, previous))
+            }
+            Ordering::Greater => {
+                h.right = Some(Self::put_internal(h.right.take(), key, value, previous))
+            }
+            Ordering::Equal => *previous = Some(mem::replace(&mut h.value, value)),
+        }
+        Self::balance(h)
+    }
+
+    /// Restore the left-leaning invariants at `h` on the way up.
+    fn balance(mut h: Box<Node<K, V>>) -> Box<Node<K, V>> {
+        if Self::is_red(&h.right) && !Self::is_red(&h.left) {
+            h = Self::rotate_left(h);
+        }
+        if Self::is_red(&h.left) && Self::is_red_left(&h.left) {
+            h = Self::rotate_right(h);
+        }
+        if Self::is_red(&h.left) && Self::is_red(&h.right) {
+            Self::flip_colors(&mut h);
+        }
+        h.n = Self::node_size(&h.left) + Self::node_size(&h.right) + 1;
+        h
+    }
+
+    fn is_red(node: &Link<K, V>) -> bool {
+        node.as_ref().is_some_and(|h| h.color == RED)
+    }
+
+    /// Whether the left child of `node` is red.
+    fn is_red_left(node: &Link<K, V>) -> bool {
+        node.as_ref().is_some_and(|h| Self::is_red(&h.left))
+    }
+
+    /// Make a right-leaning red link lean left.
+    fn rotate_left(mut h: Box<Node<K, V>>) -> Box<Node<K, V>> {
+        let mut x = h.right.take().expect("rotate_left without a right child");
+        h.right = x.left.take();
+        x.color = h.color;
+        h.color = RED;
+        x.n = h.n;
+        h.n = Self::node_size(&h.left) + Self::node_size(&h.right) + 1;
+        x.left = Some(h);
+        x
+    }
+
+    /// Make a left-leaning red link lean right.
+    fn rotate_right(mut h: Box<Node<K, V>>) -> Box<Node<K, V>> {
+        let mut x = h.left.take().expect("rotate_right without a left child");
+        h.left = x.right.take();
+        x.color = h.color;
+        h.color = RED;
+        x.n = h.n;
+        h.n = Self::node_size(&h.left) + Self::node_size(&h.right) + 1;
+        x.right = Some(h);
+        x
+    }
+
+    /// Flip the colors of `h` and its children: splits a temporary 4-node
+    /// going up, or joins two 2-nodes going down.
+    fn flip_colors(h: &mut Node<K, V>) {
+        h.color = !h.color;
+        for child in [&mut h.left, &mut h.right].iter_mut() {
+            if let Some(ref mut child) = **child {
+                child.color = !child.color;
+            }
+        }
+    }
+
+    /// Make `h.left` or one of its children red, by borrowing from its
+    /// sibling or merging with it, so the left spine can shed a key.
+    fn move_red_left(mut h: Box<Node<K, V>>) -> Box<Node<K, V>> {
+        Self::flip_colors(&mut h);
+        if Self::is_red_left(&h.right) {
+            h.right = h.right.take().map(Self::rotate_right);
+            h = Self::rotate_left(h);
+            Self::flip_colors(&mut h);
+        }
+        h
+    }
+
+    /// Make `h.right` or one of its children red, the mirror image of
+    /// `move_red_left`.
+    fn move_red_right(mut h: Box<Node<K, V>>) -> Box<Node<K, V>> {
+        Self::flip_colors(&mut h);
+        if Self::is_red_left(&h.left) {
+            h = Self::rotate_right(h);
+            Self::flip_colors(&mut h);
+        }
+        h
+    }
+
+    fn node_size(node: &Link<K, V>) -> usize {
+        node.as_ref().map_or(0, |h| h.n)
+    }
+
+    /// Get the minimal key.
+    pub fn min(&self) -> Option<&K> {
+        let mut h = self.root.as_ref()?;
+        while let Some(ref left) = h.left {
+            h = left;
+        }
+        Some(&h.key)
+    }
+
+    /// Get the maximum key.
+    pub fn max(&self) -> Option<&K> {
+        let mut h = self.root.as_ref()?;
+        while let Some(ref right) = h.right {
+            h = right;
+        }
+        Some(&h.key)
+    }
+
+    /// Get the largest key in the tree less than or equal to `key`.
+    /// ```
+    /// # use algorithm_exercise::*;
+    /// let mut tree = BalancedSearchTree::new();
+    /// tree.put(10, "ten");
+    /// tree.put(20, "twenty");
+    /// assert_eq!(tree.floor(&19), Some(&10));
+    /// assert_eq!(tree.floor(&9), None);
+    /// ```
+    pub fn floor<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&K>
+    where
+        K: Borrow<Q>,
+    {
+        let mut node = &self.root;
+        let mut floor = None;
+        while let Some(ref h) = *node {
+            node = match key.cmp(h.key.borrow()) {
+                Ordering::Less => &h.left,
+                Ordering::Greater => {
+                    floor = Some(&h.key);
+                    &h.right
                 }
+                Ordering::Equal => return Some(&h.key),
+            };
+        }
+        floor
+    }
 
-                let mut return_node = node.clone();
-                if Self::is_red(&h.right) && !Self::is_red(&h.left) {
-                    return_node = Self::rotate_left(&node)
+    /// Get the smallest key in the tree greater than or equal to `key`.
+    pub fn ceiling<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&K>
+    where
+        K: Borrow<Q>,
+    {
+        let mut node = &self.root;
+        let mut ceiling = None;
+        while let Some(ref h) = *node {
+            node = match key.cmp(h.key.borrow()) {
+                Ordering::Less => {
+                    ceiling = Some(&h.key);
+                    &h.left
                 }
-                if Self::is_red(&h.left) && Self::is_red(&(&h.left).borrow().as_ref().unwrap().left) {
-                    return_node = Self::rotate_right(&node)
+                Ordering::Greater => &h.right,
+                Ordering::Equal => return Some(&h.key),
+            };
+        }
+        ceiling
+    }
+
+    /// Get the key of rank `rank`, the one with precisely `rank` smaller keys.
+    pub fn key_of_rank(&self, mut rank: usize) -> Option<&K> {
+        let mut node = &self.root;
+        while let Some(ref h) = *node {
+            let t = Self::node_size(&h.left);
+            node = match rank.cmp(&t) {
+                Ordering::Less => &h.left,
+                Ordering::Greater => {
+                    rank -= t + 1;
+                    &h.right
                 }
-                if Self::is_red(&h.left) && Self::is_red(&h.right) {
-                    Self::flip_color(&node)
+                Ordering::Equal => return Some(&h.key),
+            };
+        }
+        None
+    }
+
+    /// Number of keys smaller than `key`; inverse of `key_of_rank()`.
+    pub fn rank_of_key<Q: Ord + ?Sized>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+    {
+        let mut node = &self.root;
+        let mut rank = 0;
+        while let Some(ref h) = *node {
+            node = match key.cmp(h.key.borrow()) {
+                Ordering::Less => &h.left,
+                Ordering::Greater => {
+                    rank += Self::node_size(&h.left) + 1;
+                    &h.right
                 }
-                h.n = Self::size(&h.left) + Self::size(&h.right) + 1;
+                Ordering::Equal => return rank + Self::node_size(&h.left),
+            };
+        }
+        rank
+    }
+
+    /// Delete the node with minimal key.
+    pub fn delete_min(&mut self) {
+        self.pop_min();
+    }
+
+    /// Remove the entry with the minimal key and return it.
+    /// ```
+    /// # use algorithm_exercise::*;
+    /// let mut tree = BalancedSearchTree::new();
+    /// tree.put(2, "two");
+    /// tree.put(1, "one");
+    /// assert_eq!(tree.pop_min(), Some((1, "one")));
+    /// assert_eq!(tree.pop_min(), Some((2, "two")));
+    /// assert_eq!(tree.pop_min(), None);
+    /// ```
+    pub fn pop_min(&mut self) -> Option<(K, V)> {
+        self.delete_from_root(Self::delete_min_internal)
+    }
+
+    /// Unlink the node with the minimal key below `h`, returning what is
+    /// left of the subtree and the node.
+    fn delete_min_internal(mut h: Box<Node<K, V>>) -> (Link<K, V>, Box<Node<K, V>>) {
+        if h.left.is_none() {
+            return (h.right.take(), h);
+        }
+        if !Self::is_red(&h.left) && !Self::is_red_left(&h.left) {
+            h = Self::move_red_left(h);
+        }
+        let (left, min) = Self::delete_min_internal(h.left.take().expect("left child"));
+        h.left = left;
+        (Some(Self::balance(h)), min)
+    }
+
+    /// Delete the node with maximal key.
+    pub fn delete_max(&mut self) {
+        self.pop_max();
+    }
+
+    /// Remove the entry with the maximal key and return it.
+    pub fn pop_max(&mut self) -> Option<(K, V)> {
+        self.delete_from_root(Self::delete_max_internal)
+    }
+
+    /// Unlink the node with the maximal key below `h`, returning what is
+    /// left of the subtree and the node.
+    fn delete_max_internal(mut h: Box<Node<K, V>>) -> (Link<K, V>, Box<Node<K, V>>) {
+        if Self::is_red(&h.left) {
+            h = Self::rotate_right(h);
+        }
+        if h.right.is_none() {
+            return (h.left.take(), h);
+        }
+        if !Self::is_red(&h.right) && !Self::is_red_left(&h.right) {
+            h = Self::move_red_right(h);
+        }
+        let (right, max) = Self::delete_max_internal(h.right.take().expect("right child"));
+        h.right = right;
+        (Some(Self::balance(h)), max)
+    }
+
+    /// Delete the node of `key`.
+    pub fn delete<Q: Ord + ?Sized>(&mut self, key: &Q)
+    where
+        K: Borrow<Q>,
+    {
+        self.remove(key);
+    }
+
+    /// Remove the entry of `key` and return it, or `None` if the tree
+    /// doesn't have key `key`.
+    /// ```
+    /// # use algorithm_exercise::*;
+    /// let mut tree = BalancedSearchTree::new();
+    /// tree.put(String::from("one"), 1);
+    /// assert_eq!(tree.remove("one"), Some((String::from("one"), 1)));
+    /// assert_eq!(tree.remove("one"), None);
+    /// ```
+    pub fn remove<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+    {
+        if !self.contains(key) {
+            return None;
+        }
+        self.delete_from_root(|root| Self::delete_internal(root, key))
+    }
+
+    /// Unlink the node of `key`, which must be below `h`, returning what
+    /// is left of the subtree and the node.
+    fn delete_internal<Q: Ord + ?Sized>(
+        mut h: Box<Node<K, V>>,
+        key: &Q,
+    ) -> (Link<K, V>, Box<Node<K, V>>)
+    where
+        K: Borrow<Q>,
+    {
+        if key < h.key.borrow() {
+            if !Self::is_red(&h.left) && !Self::is_red_left(&h.left) {
+                h = Self::move_red_left(h);
+            }
+            let (left, removed) = Self::delete_internal(h.left.take().expect("left child"), key);
+            h.left = left;
+            return (Some(Self::balance(h)), removed);
+        }
+        if Self::is_red(&h.left) {
+            h = Self::rotate_right(h);
+        }
+        if key == h.key.borrow() && h.right.is_none() {
+            return (h.left.take(), h);
+        }
+        if !Self::is_red(&h.right) && !Self::is_red_left(&h.right) {
+            h = Self::move_red_right(h);
+        }
+        let right = h.right.take().expect("right child");
+        let removed = if key == h.key.borrow() {
+            // replace the key by its successor, the minimum of the right subtree
+            let (right, mut successor) = Self::delete_min_internal(right);
+            h.right = right;
+            mem::swap(&mut h.key, &mut successor.key);
+            mem::swap(&mut h.value, &mut successor.value);
+            successor
+        } else {
+            let (right, removed) = Self::delete_internal(right, key);
+            h.right = right;
+            removed
+        };
+        (Some(Self::balance(h)), removed)
+    }
 
-                return_node
+    /// Run a top-down deletion `delete` on a non-empty tree: make the root
+    /// red unless a child already is, so there's a red link to give away
+    /// on the way down, and blacken the new root afterwards.
+    fn delete_from_root<F>(&mut self, delete: F) -> Option<(K, V)>
+    where
+        F: FnOnce(Box<Node<K, V>>) -> (Link<K, V>, Box<Node<K, V>>),
+    {
+        let mut root = self.root.take()?;
+        if !Self::is_red(&root.left) && !Self::is_red(&root.right) {
+            root.color = RED;
+        }
+        let (root, removed) = delete(root);
+        self.root = root;
+        if let Some(ref mut root) = self.root {
+            root.color = BLACK;
+        }
+        Some((removed.key, removed.value))
+    }
+
+    /// Number of keys in `[lo, hi]`.
+    pub fn size_range<Q: Ord + ?Sized>(&self, lo: &Q, hi: &Q) -> usize
+    where
+        K: Borrow<Q>,
+    {
+        if hi < lo {
+            0
+        } else {
+            self.rank_of_key(hi) - self.rank_of_key(lo) + usize::from(self.contains(hi))
+        }
+    }
+
+    /// Iterate over the key value pairs in order of keys.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        self.range::<K, _>(..)
+    }
+
+    /// Iterate over the key value pairs with keys in `range`, in order.
+    /// ```
+    /// # use algorithm_exercise::*;
+    /// let mut tree = BalancedSearchTree::new();
+    /// for k in 0..10 {
+    ///     tree.put(k, k * k);
+    /// }
+    /// let squares: Vec<_> = tree.range(3..6).map(|(_, v)| *v).collect();
+    /// assert_eq!(squares, [9, 16, 25]);
+    /// assert_eq!(tree.range(..=8).rev().next(), Some((&8, &64)));
+    /// ```
+    pub fn range<Q, R>(&self, range: R) -> Iter<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        Iter(tree::Iter::new(self.root.as_deref(), range))
+    }
+
+    /// Iterate over the keys in order.
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        tree::Keys(self.iter())
+    }
+
+    /// Iterate over the values in order of keys.
+    pub fn values(&self) -> Values<'_, K, V> {
+        tree::Values(self.iter())
+    }
+
+    /// Number of levels of the tree, i.e. of nodes on the longest path
+    /// down from the root; 0 for an empty tree.
+    pub fn height(&self) -> usize {
+        tree::height(self.root.as_deref())
+    }
+
+    /// Average depth of a node, the root being at depth 0: the internal
+    /// path length divided by the number of nodes. 0 for an empty tree.
+    /// ```
+    /// # use algorithm_exercise::*;
+    /// let mut tree = BalancedSearchTree::new();
+    /// for k in &[2, 1, 3] {
+    ///     tree.put(*k, ());
+    /// }
+    /// assert_eq!(tree.height(), 2);
+    /// assert_eq!(tree.average_depth(), 2.0 / 3.0);
+    /// assert_eq!(tree.depth_histogram(), [1, 2]);
+    /// assert_eq!(tree.leaf_count(), 2);
+    /// ```
+    pub fn average_depth(&self) -> f64 {
+        tree::average_depth(self.root.as_deref())
+    }
+
+    /// Number of nodes at each depth, from the root down.
+    pub fn depth_histogram(&self) -> Vec<usize> {
+        tree::depth_histogram(self.root.as_deref())
+    }
+
+    /// Number of nodes without children.
+    pub fn leaf_count(&self) -> usize {
+        tree::leaf_count(self.root.as_deref())
+    }
+
+    /// Number of red nodes, i.e. of 3-nodes in the corresponding 2-3 tree.
+    pub fn red_count(&self) -> usize {
+        let mut reds = 0;
+        tree::walk_depths(self.root.as_deref(), |h: &Node<K, V>, _| {
+            reds += usize::from(h.color == RED)
+        });
+        reds
+    }
+
+    /// Number of black links from the root down to an empty link, the
+    /// height of the corresponding 2-3 tree. Every such path crosses the
+    /// same number in a valid tree, so this follows the leftmost one.
+    /// ```
+    /// # use algorithm_exercise::*;
+    /// let mut tree = BalancedSearchTree::new();
+    /// for k in 0..7 {
+    ///     tree.put(k, ());
+    /// }
+    /// assert_eq!(tree.black_height(), 3);
+    /// assert_eq!(tree.red_count(), 0);
+    /// tree.put(7, ());
+    /// assert_eq!(tree.red_count(), 1);
+    /// ```
+    pub fn black_height(&self) -> usize {
+        let mut black_height = 0;
+        let mut node = &self.root;
+        while let Some(ref h) = *node {
+            black_height += usize::from(h.color == BLACK);
+            node = &h.left;
+        }
+        black_height
+    }
+
+    /// The tree in Graphviz DOT, with red links drawn red and thick and
+    /// each node showing its key, value and subtree count. Render it with
+    /// e.g. `dot -Tsvg`.
+    /// ```
+    /// # use algorithm_exercise::*;
+    /// let mut tree = BalancedSearchTree::new();
+    /// tree.put(2, "two");
+    /// tree.put(1, "one");
+    /// assert!(tree.to_dot().contains("n0 -> n1 [color=red, penwidth=2];"));
+    /// ```
+    pub fn to_dot(&self) -> String
+    where
+        K: fmt::Display,
+        V: fmt::Display,
+    {
+        render::dot(&self.drawing())
+    }
+
+    /// The tree as an SVG picture laid out without Graphviz, keys left to
+    /// right in order and one row per level, with red links thick and red.
+    /// Hovering over a node shows its value and subtree count.
+    pub fn to_svg(&self) -> String
+    where
+        K: fmt::Display,
+        V: fmt::Display,
+    {
+        render::svg(&self.drawing())
+    }
+
+    fn drawing(&self) -> Vec<Drawn>
+    where
+        K: fmt::Display,
+        V: fmt::Display,
+    {
+        render::drawing(self.root.as_deref(), |h: &Node<K, V>| h.color == RED)
+    }
+
+    /// Check the invariants of the tree: those of `BinarySearchTree::check`
+    /// plus the red-black ones, no node with two red links, no
+    /// right-leaning red link and the same number of black links on every
+    /// path from the root.
+    /// ```
+    /// # use algorithm_exercise::*;
+    /// let mut tree = BalancedSearchTree::new();
+    /// for k in 0..10 {
+    ///     tree.put(k, ());
+    /// }
+    /// tree.delete(&3);
+    /// assert!(tree.check().is_ok());
+    /// ```
+    pub fn check(&self) -> Report {
+        let mut report = Report::default();
+        tree::check(
+            self.root.as_deref(),
+            &mut report.violations,
+            |h: &Node<K, V>, position, left, right, violations| {
+                if h.color == RED && Self::is_red(&h.left) {
+                    violations.push(Violation::TwoReds { node: position });
+                }
+                if Self::is_red(&h.right) {
+                    violations.push(Violation::RightLeaningRed { node: position });
+                }
+                if left != right {
+                    violations.push(Violation::BlackHeight {
+                        node: position,
+                        left,
+                        right,
+                    });
+                }
+                left + usize::from(h.color == BLACK)
             },
-            None => Rc::new(RefCell::new(Some(Node::new(key, value, 1,RED))))
+        );
+        report
+    }
+}
+
+impl<K: Ord, V> Default for BalancedSearchTree<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord + fmt::Debug, V: fmt::Debug> fmt::Debug for BalancedSearchTree<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, K: Ord, V> IntoIterator for &'a BalancedSearchTree<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Iter<'a, K, V> {
+        self.iter()
+    }
+}
+
+impl<K: Ord, V> OrderedSymbolTable<K, V> for BalancedSearchTree<K, V> {
+    fn put(&mut self, key: K, value: V) -> Option<V> {
+        self.put(key, value)
+    }
+
+    fn get<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+    {
+        self.get(key)
+    }
+
+    fn delete<Q: Ord + ?Sized>(&mut self, key: &Q)
+    where
+        K: Borrow<Q>,
+    {
+        self.delete(key)
+    }
+
+    fn size(&self) -> usize {
+        self.size()
+    }
+
+    fn min(&self) -> Option<&K> {
+        self.min()
+    }
+
+    fn max(&self) -> Option<&K> {
+        self.max()
+    }
+
+    fn floor<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&K>
+    where
+        K: Borrow<Q>,
+    {
+        self.floor(key)
+    }
+
+    fn ceiling<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&K>
+    where
+        K: Borrow<Q>,
+    {
+        self.ceiling(key)
+    }
+
+    fn rank<Q: Ord + ?Sized>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+    {
+        self.rank_of_key(key)
+    }
+
+    fn select(&self, rank: usize) -> Option<&K> {
+        self.key_of_rank(rank)
+    }
+
+    fn keys<'a, Q: Ord + ?Sized>(&'a self, lo: &Q, hi: &Q) -> Box<dyn Iterator<Item = &'a K> + 'a>
+    where
+        K: Borrow<Q>,
+    {
+        Box::new(tree::Keys(
+            self.range((Bound::Included(lo), Bound::Included(hi))),
+        ))
+    }
+}
+
+/// Iterator over the key value pairs of a `BalancedSearchTree`, created by
+/// `iter` and `range`.
+pub struct Iter<'a, K, V>(tree::Iter<'a, Node<K, V>>);
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<(&'a K, &'a V)> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Iter<'a, K, V> {
+    fn next_back(&mut self) -> Option<(&'a K, &'a V)> {
+        self.0.next_back()
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+/// Iterator over the keys of a `BalancedSearchTree`, created by `keys`.
+pub type Keys<'a, K, V> = tree::Keys<Iter<'a, K, V>>;
+
+/// Iterator over the values of a `BalancedSearchTree`, created by `values`.
+pub type Values<'a, K, V> = tree::Values<Iter<'a, K, V>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random;
+    use crate::symbol_table::tests::random_changes;
+    use alloc::format;
+    use alloc::string::String;
+
+    #[test]
+    fn test() {
+        let mut table = BalancedSearchTree::new();
+        assert!(table.is_empty());
+        assert_eq!(table.min(), None);
+        assert_eq!(table.key_of_rank(0), None);
+        table.put(3, "3");
+        table.put(4, "4");
+        table.put(2, "2");
+        assert_eq!(table.size(), 3);
+        assert_eq!(table.get(&3), Some(&"3"));
+        assert_eq!(table.get(&1), None);
+        assert_eq!(table.floor(&5), Some(&4));
+        assert_eq!(table.floor(&1), None);
+        assert_eq!(table.ceiling(&1), Some(&2));
+        assert_eq!(table.ceiling(&5), None);
+        assert_eq!(table.key_of_rank(2), Some(&4));
+        assert_eq!(table.rank_of_key(&4), 2);
+        assert_eq!(table.rank_of_key(&5), 3);
+        assert_eq!(table.min(), Some(&2));
+        assert_eq!(table.max(), Some(&4));
+        assert_eq!(table.size_range(&3, &9), 2);
+        assert_eq!(table.size_range(&4, &3), 0);
+        assert_eq!(format!("{:?}", table), r#"{2: "2", 3: "3", 4: "4"}"#);
+
+        // sorted insertions, the worst case of an unbalanced tree
+        let mut table = BalancedSearchTree::new();
+        for k in 0..1023 {
+            table.put(k, k * 10);
+        }
+        assert!(table.height() <= 2 * 10);
+        assert_eq!(table.range(3..=6).len(), 4);
+        assert_eq!(table.range(1000..).next_back(), Some((&1022, &10220)));
+        assert_eq!(
+            table
+                .range((Bound::Excluded(2), Bound::Excluded(5)))
+                .collect::<Vec<_>>(),
+            [(&3, &30), (&4, &40)]
+        );
+        assert_eq!(table.values().next_back(), Some(&10220));
+        assert_eq!((&table).into_iter().count(), 1023);
+
+        // owned keys, looked up by a borrowed form
+        let mut table = BalancedSearchTree::new();
+        for word in "the quick brown fox jumps over the lazy dog".split(' ') {
+            let n = table.get(word).map_or(0, |n| n + 1);
+            table.put(String::from(word), n);
+        }
+        *table.get_mut("dog").unwrap() = 5;
+        assert_eq!(table.size(), 8);
+        assert_eq!(table.get("the"), Some(&1));
+        assert_eq!(table.key_of_rank(7).map(String::as_str), Some("the"));
+        assert_eq!(table.floor("p").map(String::as_str), Some("over"));
+        assert_eq!(table.values().sum::<i32>(), 6);
+
+        // random changes keep the tree balanced
+        let mut table = BalancedSearchTree::new();
+        let mut round = 0;
+        let model = random_changes(&mut table, 45, 6000, 1000, |table, _| {
+            if round % 50 == 0 {
+                assert!(table.check().is_ok(), "{}", table.check());
+                if let Some(ref root) = table.root {
+                    assert_eq!(root.color, BLACK);
+                }
+            }
+            round += 1;
+        });
+        assert!(table.iter().eq(model.iter()));
+        assert!(table.height() <= 2 * 10);
+
+        // deleting everything keeps the tree balanced at every step
+        let mut table = BalancedSearchTree::new();
+        for k in 0..255 {
+            table.put(k, ());
+        }
+        for k in (0..255).step_by(3) {
+            table.delete(&k);
+            assert!(table.check().is_ok(), "{}", table.check());
+        }
+        while !table.is_empty() {
+            table.delete_max();
+            assert!(table.check().is_ok(), "{}", table.check());
+            table.delete_min();
+            assert!(table.check().is_ok(), "{}", table.check());
+        }
+        table.delete_min();
+        table.delete(&3);
+        assert_eq!(table.pop_max(), None);
+
+        // random keys give an average depth near lg n, and height within
+        // twice the black height; lg 10000 = 13.3
+        let mut keys: Vec<u32> = (0..10_000).collect();
+        random::shuffle(&mut keys, &mut random::seeded(49));
+        let mut table = BalancedSearchTree::new();
+        for &k in &keys {
+            table.put(k, ());
+        }
+        let average = table.average_depth() + 1.0;
+        assert!(average < 13.3, "{}", average);
+        assert!(table.height() <= 2 * table.black_height());
+        assert!(table.black_height() <= 14);
+        let histogram = table.depth_histogram();
+        assert_eq!(histogram.iter().sum::<usize>(), 10_000);
+        assert_eq!(histogram[..3], [1, 2, 4]);
+        assert!(table.red_count() > 0 && table.red_count() < 10_000 / 2);
+        assert!(table.leaf_count() > 10_000 / 4);
+        // the 2-3 tree has one node per black node
+        let black = table.size() - table.red_count();
+        assert!(1 << (table.black_height() - 1) <= black);
+        assert_eq!(BalancedSearchTree::<u32, ()>::new().black_height(), 0);
+
+        // drawings show the red link of a 3-node
+        let mut table = BalancedSearchTree::new();
+        table.put(1, "one");
+        table.put(2, "two");
+        let dot = table.to_dot();
+        assert!(dot.contains("n0 [label=\"2\\ntwo\\nn = 2\"];"), "{}", dot);
+        assert!(dot.contains("n0 -> n1 [color=red, penwidth=2];"), "{}", dot);
+        let svg = table.to_svg();
+        assert_eq!(svg.matches("stroke=\"red\"").count(), 1);
+        assert!(svg.contains("<title>1: one (n = 1)</title>"));
+
+        // broken trees are reported
+        let mut table = BalancedSearchTree::new();
+        for k in 1..=3 {
+            table.put(k, ());
+        }
+        assert!(table.check().is_ok());
+        let root = table.root.as_mut().unwrap();
+        root.right.as_mut().unwrap().color = RED;
+        assert_eq!(
+            table.check().violations,
+            [
+                Violation::RightLeaningRed { node: 1 },
+                Violation::BlackHeight {
+                    node: 1,
+                    left: 1,
+                    right: 0
+                },
+            ]
+        );
+        let root = table.root.as_mut().unwrap();
+        root.right.as_mut().unwrap().color = BLACK;
+        root.color = RED;
+        root.left.as_mut().unwrap().color = RED;
+        root.n = 4;
+        assert_eq!(
+            table.check().violations,
+            [
+                Violation::Count {
+                    node: 1,
+                    n: 4,
+                    actual: 3
+                },
+                Violation::TwoReds { node: 1 },
+                Violation::BlackHeight {
+                    node: 1,
+                    left: 0,
+                    right: 1
+                },
+            ]
+        );
+
+        // a degenerate tree is checked without deep recursion
+        let mut chain: Link<u32, ()> = None;
+        for k in (0..100_000).rev() {
+            let mut h = Node::new(k, (), 100_000 - k as usize, BLACK);
+            h.right = chain.take();
+            chain = Some(Box::new(h));
+        }
+        let table = BalancedSearchTree { root: chain };
+        let violations = table.check().violations;
+        assert_eq!(violations.len(), 99_999);
+        assert_eq!(
+            violations[0],
+            Violation::BlackHeight {
+                node: 99_998,
+                left: 0,
+                right: 1
+            }
+        );
+        // unlink it by hand, dropping it whole would recurse
+        let mut chain = table.root;
+        while let Some(mut h) = chain {
+            chain = h.right.take();
         }
     }
-   
 }
@@ -0,0 +1,342 @@
+//! Sort lines of text with the algorithms of `algorithm_exercise`.
+//!
+//! ```text
+//! sort [--algo quick|shell|insertion|selection|bubble] [-n] [-r] [-u]
+//!      [-k N[,M]] [-t DELIM] [--stats] [FILE...]
+//! ```
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::process;
+
+use algorithm_exercise::SortExt;
+
+const USAGE: &str = "\
+usage: sort [OPTION]... [FILE]...
+Sort lines of the FILEs (standard input if none, or if FILE is -).
+
+      --algo NAME  quick (default), shell, insertion, selection or bubble
+  -n               compare keys as numbers
+  -r               reverse the order
+  -u               print only the first line read of those with equal keys
+  -k N[,M]         use fields N to M (from 1), or N to the end of the line,
+                   as key instead of the line
+  -t DELIM         fields are separated by DELIM instead of whitespace
+      --stats      print the number of comparisons to standard error
+  -h, --help       print this help";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Algo {
+    Quick,
+    Shell,
+    Insertion,
+    Selection,
+    Bubble,
+}
+
+#[derive(Debug, PartialEq)]
+struct Options {
+    algo: Algo,
+    numeric: bool,
+    reverse: bool,
+    unique: bool,
+    /// The first field of the key and the last, if not the last of the line.
+    field: Option<(usize, Option<usize>)>,
+    delimiter: Option<char>,
+    stats: bool,
+    help: bool,
+    files: Vec<String>,
+}
+
+fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<Options, String> {
+    let mut options = Options {
+        algo: Algo::Quick,
+        numeric: false,
+        reverse: false,
+        unique: false,
+        field: None,
+        delimiter: None,
+        stats: false,
+        help: false,
+        files: vec![],
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--algo" => {
+                let name = args.next().ok_or("--algo needs a NAME")?;
+                options.algo = match name.as_str() {
+                    "quick" => Algo::Quick,
+                    "shell" => Algo::Shell,
+                    "insertion" => Algo::Insertion,
+                    "selection" => Algo::Selection,
+                    "bubble" => Algo::Bubble,
+                    _ => return Err(format!("unknown algorithm '{}'", name)),
+                }
+            }
+            "--stats" => options.stats = true,
+            "-h" | "--help" => options.help = true,
+            "-" => options.files.push(arg),
+            _ if arg.starts_with('-') && !arg.starts_with("--") => {
+                let mut flags = arg[1..].chars();
+                while let Some(flag) = flags.next() {
+                    match flag {
+                        'n' => options.numeric = true,
+                        'r' => options.reverse = true,
+                        'u' => options.unique = true,
+                        'k' | 't' => {
+                            // the value is the rest of this argument or the next one
+                            let rest: String = flags.by_ref().collect();
+                            let value = if rest.is_empty() {
+                                args.next().ok_or(format!("-{} needs a value", flag))?
+                            } else {
+                                rest
+                            };
+                            if flag == 'k' {
+                                options.field = Some(
+                                    parse_fields(&value)
+                                        .ok_or(format!("invalid field '{}'", value))?,
+                                );
+                            } else {
+                                let mut chars = value.chars();
+                                match (chars.next(), chars.next()) {
+                                    (Some(c), None) => options.delimiter = Some(c),
+                                    _ => return Err(format!("invalid delimiter '{}'", value)),
+                                }
+                            }
+                        }
+                        _ => return Err(format!("unknown option -{}", flag)),
+                    }
+                }
+            }
+            _ if arg.starts_with("--") => return Err(format!("unknown option {}", arg)),
+            _ => options.files.push(arg),
+        }
+    }
+    Ok(options)
+}
+
+/// The fields of `-k N[,M]`, numbered from 1.
+fn parse_fields(value: &str) -> Option<(usize, Option<usize>)> {
+    let field = |n: &str| n.parse().ok().filter(|&n| n > 0);
+    match value.find(',') {
+        None => Some((field(value)?, None)),
+        Some(i) => Some((field(&value[..i])?, Some(field(&value[i + 1..])?))),
+    }
+}
+
+/// Where the fields of `line` start and end. As in sort(1), without a
+/// delimiter a field starts with the blanks before it.
+fn field_spans(line: &str, delimiter: Option<char>) -> Vec<(usize, usize)> {
+    let mut spans = vec![];
+    let mut start = 0;
+    let mut blank = true;
+    for (i, c) in line.char_indices() {
+        match delimiter {
+            Some(d) if c == d => {
+                spans.push((start, i));
+                start = i + c.len_utf8();
+            }
+            Some(_) => {}
+            None if c.is_whitespace() => {
+                if !blank {
+                    spans.push((start, i));
+                    start = i;
+                }
+                blank = true;
+            }
+            None => blank = false,
+        }
+    }
+    spans.push((start, line.len()));
+    spans
+}
+
+/// The part of `line` that is compared.
+fn key<'a>(line: &'a str, options: &Options) -> &'a str {
+    let (first, last) = match options.field {
+        None => return line,
+        Some(field) => field,
+    };
+    let spans = field_spans(line, options.delimiter);
+    let start = spans.get(first - 1).map_or(line.len(), |span| span.0);
+    let end = match last {
+        Some(last) => spans.get(last - 1).map_or(line.len(), |span| span.1),
+        None => line.len(),
+    };
+    &line[start..end.max(start)]
+}
+
+fn compare_keys(a: &str, b: &str, options: &Options) -> Ordering {
+    if options.numeric {
+        leading_number(a).total_cmp(&leading_number(b))
+    } else {
+        a.cmp(b)
+    }
+}
+
+/// The number `s` starts with, ignoring leading blanks. Like sort(1),
+/// text that doesn't start with a number counts as 0.
+fn leading_number(s: &str) -> f64 {
+    let s = s.trim_start();
+    let mut end = 0;
+    let mut seen_point = false;
+    for (i, c) in s.char_indices() {
+        match c {
+            '-' | '+' if i == 0 => {}
+            '.' if !seen_point => seen_point = true,
+            '0'..='9' => {}
+            _ => break,
+        }
+        end = i + 1;
+    }
+    s[..end].parse().unwrap_or(0.0)
+}
+
+fn read_lines(options: &Options) -> io::Result<Vec<String>> {
+    let mut lines = vec![];
+    let stdin = ["-".to_string()];
+    let files = if options.files.is_empty() {
+        &stdin[..]
+    } else {
+        &options.files[..]
+    };
+    for file in files {
+        let reader: Box<dyn BufRead> = if file == "-" {
+            Box::new(BufReader::new(io::stdin()))
+        } else {
+            let f = File::open(file)
+                .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", file, e)))?;
+            Box::new(BufReader::new(f))
+        };
+        for line in reader.lines() {
+            lines.push(line?);
+        }
+    }
+    Ok(lines)
+}
+
+/// Sort `lines` as `options` say, returning the number of comparisons made.
+fn sort(lines: &mut Vec<String>, options: &Options) -> usize {
+    let comparisons = Cell::new(0);
+    let compare = |(i, a): &(usize, String), (j, b): &(usize, String)| {
+        comparisons.set(comparisons.get() + 1);
+        let ordering = compare_keys(key(a, options), key(b, options), options);
+        let (ordering, a, b) = if options.reverse {
+            (ordering.reverse(), b, a)
+        } else {
+            (ordering, a, b)
+        };
+        // equal keys are ordered by the whole line, as sort(1) does, except
+        // that -u keeps them in input order, to print the first one read
+        if options.unique {
+            ordering.then_with(|| i.cmp(j))
+        } else {
+            ordering.then_with(|| a.cmp(b))
+        }
+    };
+    let mut tagged: Vec<(usize, String)> = lines.drain(..).enumerate().collect();
+    match options.algo {
+        Algo::Quick => tagged.quicksort_by(compare),
+        Algo::Shell => tagged.shellsort_by(compare),
+        Algo::Insertion => tagged.insertion_sort_by(compare),
+        Algo::Selection => tagged.selection_sort_by(compare),
+        Algo::Bubble => tagged.bubble_sort_by(compare),
+    }
+    if options.unique {
+        tagged.dedup_by(|(_, b), (_, a)| {
+            compare_keys(key(a, options), key(b, options), options) == Ordering::Equal
+        });
+    }
+    lines.extend(tagged.into_iter().map(|(_, line)| line));
+    comparisons.get()
+}
+
+fn main() {
+    let options = match parse_args(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("sort: {}", message);
+            process::exit(2);
+        }
+    };
+    if options.help {
+        let _ = writeln!(io::stdout(), "{}", USAGE);
+        return;
+    }
+    let mut lines = match read_lines(&options) {
+        Ok(lines) => lines,
+        Err(e) => {
+            eprintln!("sort: {}", e);
+            process::exit(2);
+        }
+    };
+    let comparisons = sort(&mut lines, &options);
+
+    let stdout = io::stdout();
+    let mut out = BufWriter::new(stdout.lock());
+    for line in &lines {
+        if writeln!(out, "{}", line).is_err() {
+            process::exit(2);
+        }
+    }
+    if out.flush().is_err() {
+        process::exit(2);
+    }
+    if options.stats {
+        eprintln!("comparisons: {}", comparisons);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let args = |s: &str| parse_args(s.split_whitespace().map(String::from));
+        let options = args("--algo shell -nru -k2 -t , --stats a.txt -").unwrap();
+        assert_eq!(options.algo, Algo::Shell);
+        assert!(options.numeric && options.reverse && options.unique && options.stats);
+        assert_eq!(options.field, Some((2, None)));
+        assert_eq!(options.delimiter, Some(','));
+        assert_eq!(options.files, ["a.txt", "-"]);
+        assert_eq!(args("-k2,3").unwrap().field, Some((2, Some(3))));
+        assert!(args("--algo bogo").is_err());
+        assert!(args("-k 0").is_err());
+        assert!(args("-k 2,").is_err());
+        assert!(args("-k 2.1").is_err());
+        assert!(args("-x").is_err());
+
+        assert!(args("-h").unwrap().help);
+        assert_eq!(leading_number(" -1.5e3 apples"), -1.5);
+        assert_eq!(leading_number("+7"), 7.0);
+        assert_eq!(leading_number("x"), 0.0);
+
+        let line = "a  b c";
+        assert_eq!(key(line, &args("-k2").unwrap()), "  b c");
+        assert_eq!(key(line, &args("-k2,2").unwrap()), "  b");
+        assert_eq!(key(line, &args("-k4").unwrap()), "");
+        assert_eq!(key(line, &args("-k3,2").unwrap()), "");
+        assert_eq!(key("a,b,,d", &args("-k2,3 -t,").unwrap()), "b,");
+        assert_eq!(key("a,b,,d", &args("-k3 -t,").unwrap()), ",d");
+
+        let lines = ["b,10", "a,9", "c,10", "d,x"];
+        for algo in &["quick", "shell", "insertion", "selection", "bubble"] {
+            let options = args(&format!("--algo {} -n -k 2 -t,", algo)).unwrap();
+            let mut sorted: Vec<String> = lines.iter().map(|s| s.to_string()).collect();
+            assert!(sort(&mut sorted, &options) > 0);
+            assert_eq!(sorted, ["d,x", "a,9", "b,10", "c,10"]);
+        }
+        let lines = ["x 2", "y 1", "z 2"];
+        let sorted = |options: &str| {
+            let mut sorted: Vec<String> = lines.iter().map(|s| s.to_string()).collect();
+            sort(&mut sorted, &args(options).unwrap());
+            sorted
+        };
+        assert_eq!(sorted("-u -r -k2"), ["x 2", "y 1"]);
+        assert_eq!(sorted("-r -k2"), ["z 2", "x 2", "y 1"]);
+        assert_eq!(sorted("-u -k2,2"), ["y 1", "x 2"]);
+    }
+}
@@ -1,39 +1,85 @@
-use std::cell::RefCell;
-use std::fmt;
-use std::rc::Rc;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::fmt;
+use core::mem;
+use core::ops::{Bound, RangeBounds};
 
-pub struct BinarySearchTree<T: PartialOrd + Copy + fmt::Debug, U: fmt::Debug> {
-    root: Rc<RefCell<Option<BTNode<T, U>>>>,
+use crate::invariants::Report;
+use crate::render::{self, Drawn};
+use crate::symbol_table::OrderedSymbolTable;
+use crate::tree::{self, TreeNode};
+
+type Link<T, U> = Option<Box<BTNode<T, U>>>;
+
+/// Ordered symbol table. Lookups take any borrowed form of the key, like
+/// `BTreeMap`: a `BinarySearchTree<String, _>` can be searched by `&str`.
+/// ```
+/// # use algorithm_exercise::*;
+/// let mut tree = BinarySearchTree::new();
+/// tree.put(String::from("two"), 2);
+/// tree.put(String::from("one"), 1);
+/// assert_eq!(tree.get("two"), Some(&2));
+/// assert_eq!(tree.min().map(String::as_str), Some("one"));
+/// ```
+pub struct BinarySearchTree<T, U> {
+    root: Link<T, U>,
 }
 
 /// Binary search tree node
 #[derive(Debug)]
-struct BTNode<T: PartialOrd + Copy + fmt::Debug, U: fmt::Debug> {
+struct BTNode<T, U> {
     key: T,
     value: U,
-    left: Rc<RefCell<Option<BTNode<T, U>>>>,
-    right: Rc<RefCell<Option<BTNode<T, U>>>>,
+    left: Link<T, U>,
+    right: Link<T, U>,
     n: usize,
 }
 
-impl<T: PartialOrd + Copy + fmt::Debug, U: Clone + fmt::Debug> BTNode<T, U> {
+impl<T: Ord, U> BTNode<T, U> {
     fn new(key: T, value: U, n: usize) -> BTNode<T, U> {
         BTNode {
             key,
             value,
-            left: Rc::new(RefCell::new(None)),
-            right: Rc::new(RefCell::new(None)),
+            left: None,
+            right: None,
             n,
         }
     }
 }
 
-impl<T: PartialOrd + Copy + fmt::Debug, U: Clone + fmt::Debug> BinarySearchTree<T, U> {
+impl<T, U> TreeNode for BTNode<T, U> {
+    type Key = T;
+    type Value = U;
+
+    fn key(&self) -> &T {
+        &self.key
+    }
+
+    fn value(&self) -> &U {
+        &self.value
+    }
+
+    fn left(&self) -> Option<&Self> {
+        self.left.as_deref()
+    }
+
+    fn right(&self) -> Option<&Self> {
+        self.right.as_deref()
+    }
+
+    fn size(&self) -> usize {
+        self.n
+    }
+}
+
+impl<T: Ord, U> BinarySearchTree<T, U> {
     /// Initializer.
     pub fn new() -> Self {
-        BinarySearchTree {
-            root: Rc::new(RefCell::new(None)),
-        }
+        BinarySearchTree { root: None }
     }
 
     /// Return size of the tree, ie. count of nodes.
@@ -44,11 +90,27 @@ impl<T: PartialOrd + Copy + fmt::Debug, U: Clone + fmt::Debug> BinarySearchTree<
     /// assert_eq!(tree.size(), 0);
     /// ```
     pub fn size(&self) -> usize {
-        if let Some(ref root) = *self.root.borrow() {
-            root.n
-        } else {
-            0
-        }
+        Self::node_size(&self.root)
+    }
+
+    /// Whether the tree has no keys.
+    pub fn is_empty(&self) -> bool {
+        self.size() == 0
+    }
+
+    /// Whether the tree has key `key`.
+    /// ```
+    /// # use algorithm_exercise::*;
+    /// let mut tree = BinarySearchTree::new();
+    /// tree.put(1, "one");
+    /// assert!(tree.contains(&1));
+    /// assert!(!tree.contains(&2));
+    /// ```
+    pub fn contains<Q: Ord + ?Sized>(&self, key: &Q) -> bool
+    where
+        T: Borrow<Q>,
+    {
+        self.get(key).is_some()
     }
 
     /// Get the value for `key`. Returns `None` if the tree doesn't have key `key`.
@@ -57,58 +119,134 @@ impl<T: PartialOrd + Copy + fmt::Debug, U: Clone + fmt::Debug> BinarySearchTree<
     /// let mut tree = BinarySearchTree::new();
     /// tree.put("one",1);
     /// tree.put("two",2);
-    /// assert_eq!(tree.get("two"), Some(2));
+    /// assert_eq!(tree.get("two"), Some(&2));
     /// assert_eq!(tree.get(""), None);
     /// ```
-    pub fn get(&self, key: T) -> Option<U> {
-        Self::get_internal(&self.root, key)
+    pub fn get<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&U>
+    where
+        T: Borrow<Q>,
+    {
+        let mut node = &self.root;
+        while let Some(ref b_node) = *node {
+            node = match key.cmp(b_node.key.borrow()) {
+                Ordering::Less => &b_node.left,
+                Ordering::Greater => &b_node.right,
+                Ordering::Equal => return Some(&b_node.value),
+            };
+        }
+        None
     }
 
-    fn get_internal(node: &Rc<RefCell<Option<BTNode<T, U>>>>, key: T) -> Option<U> {
-        if let Some(ref node) = *node.borrow() {
-            // let ref_node = node.borrow();
-            if node.key > key {
-                Self::get_internal(&node.left, key)
-            } else if node.key < key {
-                Self::get_internal(&node.right, key)
-            } else {
-                Some(node.value.clone())
+    /// Get a mutable reference to the value for `key`.
+    pub fn get_mut<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<&mut U>
+    where
+        T: Borrow<Q>,
+    {
+        self.node_mut(key).map(|b_node| &mut b_node.value)
+    }
+
+    fn node_mut<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<&mut BTNode<T, U>>
+    where
+        T: Borrow<Q>,
+    {
+        let mut node = &mut self.root;
+        while let Some(ref mut b_node) = *node {
+            node = match key.cmp(b_node.key.borrow()) {
+                Ordering::Less => &mut b_node.left,
+                Ordering::Greater => &mut b_node.right,
+                Ordering::Equal => return Some(b_node),
+            };
+        }
+        None
+    }
+
+    /// Apply `f` to the value for `key`. Returns whether the tree has key
+    /// `key`.
+    /// ```
+    /// # use algorithm_exercise::*;
+    /// let mut tree = BinarySearchTree::new();
+    /// tree.put("a", 1);
+    /// assert!(tree.update("a", |v| *v += 1));
+    /// assert!(!tree.update("b", |v| *v += 1));
+    /// assert_eq!(tree.get("a"), Some(&2));
+    /// ```
+    pub fn update<Q: Ord + ?Sized, F: FnOnce(&mut U)>(&mut self, key: &Q, f: F) -> bool
+    where
+        T: Borrow<Q>,
+    {
+        match self.get_mut(key) {
+            Some(value) => {
+                f(value);
+                true
             }
-        } else {
-            None
+            None => false,
         }
     }
 
-    /// Insert key value pair into tree. If key already exists, update the corresponding value.
-    pub fn put(&self, key: T, val: U) {
-        // println!("put {:?}:{:?}",key, val );
-        BinarySearchTree::put_internal(&self.root, key, val);
-    }
-
-    fn put_internal(node: &Rc<RefCell<Option<BTNode<T, U>>>>, key: T, val: U) {
-        let mut m = node.borrow_mut();
-        if let Some(ref mut node) = *m {
-            // println!("some");
-            // let mut ref_node = node.borrow_mut();
-            if node.key > key {
-                // println!("smaller");
-                BinarySearchTree::put_internal(&node.left, key, val);
-            } else if node.key < key {
-                // println!("bigger");
-                BinarySearchTree::put_internal(&node.right, key, val);
+    /// Get the entry of `key`, to read, change or insert its value with a
+    /// single descent of the tree.
+    /// ```
+    /// # use algorithm_exercise::*;
+    /// let mut counts = BinarySearchTree::new();
+    /// for word in "to be or not to be".split(' ') {
+    ///     *counts.entry(word).or_insert(0) += 1;
+    /// }
+    /// assert_eq!(counts.get("to"), Some(&2));
+    /// assert_eq!(counts.get("or"), Some(&1));
+    /// ```
+    pub fn entry(&mut self, key: T) -> Entry<'_, T, U> {
+        // the sizes of the nodes passed, to count a new node in
+        let mut sizes = vec![];
+        let mut node = &mut self.root;
+        loop {
+            if node.is_none() {
+                return Entry::Vacant(VacantEntry {
+                    key,
+                    slot: node,
+                    sizes,
+                });
+            }
+            let b_node = &mut **node.as_mut().unwrap();
+            let ordering = key.cmp(&b_node.key);
+            if ordering == Ordering::Equal {
+                return Entry::Occupied(OccupiedEntry { node: b_node });
+            }
+            let BTNode {
+                ref mut left,
+                ref mut right,
+                ref mut n,
+                ..
+            } = *b_node;
+            sizes.push(n);
+            node = if ordering == Ordering::Less {
+                left
             } else {
-                // println!("same");
-                node.value = val;
+                right
+            };
+        }
+    }
+
+    /// Insert key value pair into tree. If key already exists, update the corresponding value.
+    /// Returns the previous value, or `None` if the key is new. Takes a
+    /// single descent, as `entry` does.
+    /// ```
+    /// # use algorithm_exercise::*;
+    /// let mut tree = BinarySearchTree::new();
+    /// assert_eq!(tree.put(1, "one"), None);
+    /// assert_eq!(tree.put(1, "uno"), Some("one"));
+    /// ```
+    pub fn put(&mut self, key: T, val: U) -> Option<U> {
+        match self.entry(key) {
+            Entry::Occupied(mut entry) => Some(entry.insert(val)),
+            Entry::Vacant(entry) => {
+                entry.insert(val);
+                None
             }
-            node.n = Self::node_size(&node.left) + Self::node_size(&node.right) + 1;
-        } else {
-            // println!("none");
-            *m = Some(BTNode::new(key, val, 1));
         }
     }
 
-    fn node_size(node: &Rc<RefCell<Option<BTNode<T, U>>>>) -> usize {
-        if let Some(ref node) = *node.borrow() {
+    fn node_size(node: &Link<T, U>) -> usize {
+        if let Some(ref node) = *node {
             node.n
         } else {
             0
@@ -123,28 +261,14 @@ impl<T: PartialOrd + Copy + fmt::Debug, U: Clone + fmt::Debug> BinarySearchTree<
     /// tree.put(1, "one");
     /// tree.put(4, "four");
     /// tree.put(0, "zero");
-    /// assert_eq!(tree.min(), Some(0))
-    /// ```
-    pub fn min(&self) -> Option<T> {
-        // match *Self::min_internal(self.root.clone()).borrow() {
-        //     Some(ref node) => Some(node.key),
-        //     None => None,
-        // }
-        Self::min_internal(self.root.clone()) // Rc<RefCell<Option<Node>>>
-            .borrow() // &Option<Node>
-            .as_ref() // Option<&Node> (auto dereffed)
-            .map(|x| x.key) // Option<T> (T copied)
-    }
-
-    fn min_internal(node: Rc<RefCell<Option<BTNode<T, U>>>>) -> Rc<RefCell<Option<BTNode<T, U>>>> {
-        // println!("{:?}", node.borrow());
-        match *node.borrow() {
-            None => node.clone(),
-            Some(ref b_node) => match *b_node.left.borrow() {
-                None => node.clone(),
-                Some(_) => Self::min_internal(b_node.left.clone()),
-            },
+    /// assert_eq!(tree.min(), Some(&0))
+    /// ```
+    pub fn min(&self) -> Option<&T> {
+        let mut b_node = self.root.as_ref()?;
+        while let Some(ref left) = b_node.left {
+            b_node = left;
         }
+        Some(&b_node.key)
     }
 
     /// Get the maximum key.
@@ -155,202 +279,646 @@ impl<T: PartialOrd + Copy + fmt::Debug, U: Clone + fmt::Debug> BinarySearchTree<
     /// tree.put(1, "one");
     /// tree.put(4, "four");
     /// tree.put(0, "zero");
-    /// assert_eq!(tree.max(), Some(4));
-    /// ```
-    pub fn max(&self) -> Option<T> {
-        Self::max_internal(self.root.clone())
-            .borrow()
-            .as_ref()
-            .map(|x| x.key)
-    }
-
-    fn max_internal(node: Rc<RefCell<Option<BTNode<T, U>>>>) -> Rc<RefCell<Option<BTNode<T, U>>>> {
-        match *node.borrow() {
-            None => node.clone(),
-            Some(ref b_node) => match *b_node.right.borrow() {
-                None => node.clone(),
-                Some(_) => Self::max_internal(b_node.right.clone()),
-            },
+    /// assert_eq!(tree.max(), Some(&4));
+    /// ```
+    pub fn max(&self) -> Option<&T> {
+        let mut b_node = self.root.as_ref()?;
+        while let Some(ref right) = b_node.right {
+            b_node = right;
         }
+        Some(&b_node.key)
     }
 
     /// Get the key of the floor node of `key`, i.e. the largest key in the BST less than or equal to `key`.
-    pub fn floor(&self, key: T) -> Option<T> {
-        Self::floor_internal(self.root.clone(), key) // Rc<RefCell<Option<Node>>>
-            .borrow() // &Option<Node>
-            .as_ref() // Option<&Node> (auto dereffed)
-            .map(|x| x.key) // Option<T> (T copied)
-    }
-
-    fn floor_internal(
-        node: Rc<RefCell<Option<BTNode<T, U>>>>,
-        key: T,
-    ) -> Rc<RefCell<Option<BTNode<T, U>>>> {
-        match *node.borrow() {
-            Some(ref b_node) => {
-                if key < b_node.key {
-                    Self::floor_internal(b_node.left.clone(), key)
-                } else if key > b_node.key {
-                    let floor_right = Self::floor_internal(b_node.right.clone(), key);
-                    match *floor_right.clone().borrow() {
-                        Some(_) => floor_right,
-                        None => node.clone(),
-                    }
-                } else {
-                    node.clone()
+    pub fn floor<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+    {
+        let mut node = &self.root;
+        let mut floor = None;
+        while let Some(ref b_node) = *node {
+            node = match key.cmp(b_node.key.borrow()) {
+                Ordering::Less => &b_node.left,
+                Ordering::Greater => {
+                    floor = Some(&b_node.key);
+                    &b_node.right
                 }
-            }
-            None => node.clone(),
+                Ordering::Equal => return Some(&b_node.key),
+            };
         }
+        floor
+    }
+
+    /// Get the ceiling of `key`, i.e. the smallest key in the BST greater than or equal to `key`.
+    /// ```
+    /// # use algorithm_exercise::*;
+    /// let mut tree = BinarySearchTree::new();
+    /// tree.put(10, "ten");
+    /// tree.put(20, "twenty");
+    /// assert_eq!(tree.ceiling(&10), Some(&10));
+    /// assert_eq!(tree.ceiling(&11), Some(&20));
+    /// assert_eq!(tree.ceiling(&21), None);
+    /// ```
+    pub fn ceiling<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+    {
+        self.key_of_rank(self.rank_of_key(key))
+    }
+
+    /// Get the smallest key in the BST strictly greater than `key`.
+    pub fn successor<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+    {
+        self.key_of_rank(self.rank_of_key(key) + usize::from(self.contains(key)))
+    }
+
+    /// Get the largest key in the BST strictly smaller than `key`.
+    pub fn predecessor<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+    {
+        self.rank_of_key(key)
+            .checked_sub(1)
+            .and_then(|rank| self.key_of_rank(rank))
     }
 
     /// Get the key of the node of rank `rank` (the key such that
     /// precisely *rank* number of other keys in the BST are smaller).
-    pub fn key_of_rank(&self, rank: usize) -> Option<T> {
-        Self::select_internal(self.root.clone(), rank)
-            .borrow()
-            .as_ref()
-            .map(|x| x.key)
-    }
-
-    fn select_internal(
-        node: Rc<RefCell<Option<BTNode<T, U>>>>,
-        rank: usize,
-    ) -> Rc<RefCell<Option<BTNode<T, U>>>> {
-        match *node.borrow() {
-            Some(ref b_node) => {
-                let t = Self::node_size(&b_node.left);
-                if t > rank {
-                    Self::select_internal(b_node.left.clone(), rank)
-                } else if t < rank {
-                    Self::select_internal(b_node.right.clone(), rank - t - 1)
-                } else {
-                    node.clone()
+    pub fn key_of_rank(&self, rank: usize) -> Option<&T> {
+        let mut node = &self.root;
+        let mut rank = rank;
+        while let Some(ref b_node) = *node {
+            let t = Self::node_size(&b_node.left);
+            node = match rank.cmp(&t) {
+                Ordering::Less => &b_node.left,
+                Ordering::Greater => {
+                    rank -= t + 1;
+                    &b_node.right
                 }
-            }
-            None => node.clone(),
+                Ordering::Equal => return Some(&b_node.key),
+            };
         }
+        None
     }
+
     /// Inverse method of `key_of_rank()`.
-    pub fn rank_of_key(&self, key: T) -> usize {
-        return Self::rank_internal(self.root.clone(), key);
-    }
-
-    fn rank_internal(node: Rc<RefCell<Option<BTNode<T, U>>>>, key: T) -> usize {
-        match *node.borrow() {
-            Some(ref b_node) => {
-                if key < b_node.key {
-                    Self::rank_internal(b_node.left.clone(), key)
-                } else if key > b_node.key {
-                    1 + Self::node_size(&b_node.left)
-                        + Self::rank_internal(b_node.right.clone(), key)
-                } else {
-                    Self::node_size(&b_node.left)
+    pub fn rank_of_key<Q: Ord + ?Sized>(&self, key: &Q) -> usize
+    where
+        T: Borrow<Q>,
+    {
+        let mut node = &self.root;
+        let mut rank = 0;
+        while let Some(ref b_node) = *node {
+            node = match key.cmp(b_node.key.borrow()) {
+                Ordering::Less => &b_node.left,
+                Ordering::Greater => {
+                    rank += Self::node_size(&b_node.left) + 1;
+                    &b_node.right
                 }
-            }
-            None => 0,
+                Ordering::Equal => return rank + Self::node_size(&b_node.left),
+            };
         }
+        rank
     }
 
     /// Delete the node with minimal value.
     pub fn delete_min(&mut self) {
-        self.root = Self::delete_min_internal(self.root.clone())
-    }
-
-    fn delete_min_internal(
-        node: Rc<RefCell<Option<BTNode<T, U>>>>,
-    ) -> Rc<RefCell<Option<BTNode<T, U>>>> {
-        match *node.borrow_mut() {
-            Some(ref mut b_node) => {
-                if b_node.left.borrow().is_none() {
-                    b_node.right.clone()
-                } else {
-                    b_node.left = Self::delete_min_internal(b_node.left.clone());
-                    b_node.n = Self::node_size(&b_node.left) + Self::node_size(&b_node.right) + 1;
-                    node.clone()
-                }
-            }
-            None => node.clone(),
+        self.pop_min();
+    }
+
+    /// Remove the entry with the minimal key and return it.
+    /// ```
+    /// # use algorithm_exercise::*;
+    /// let mut tree = BinarySearchTree::new();
+    /// tree.put(2, "two");
+    /// tree.put(1, "one");
+    /// assert_eq!(tree.pop_min(), Some((1, "one")));
+    /// assert_eq!(tree.pop_min(), Some((2, "two")));
+    /// assert_eq!(tree.pop_min(), None);
+    /// ```
+    pub fn pop_min(&mut self) -> Option<(T, U)> {
+        Self::delete_min_internal(&mut self.root).map(|node| (node.key, node.value))
+    }
+
+    /// Unlink the node with the minimal key below `node` and return it.
+    fn delete_min_internal(mut node: &mut Link<T, U>) -> Link<T, U> {
+        while node.as_ref()?.left.is_some() {
+            let b_node = node.as_mut().unwrap();
+            b_node.n -= 1;
+            node = &mut b_node.left;
+        }
+        let mut min = node.take()?;
+        *node = min.right.take();
+        Some(min)
+    }
+
+    /// Delete the node with maximal value.
+    pub fn delete_max(&mut self) {
+        self.pop_max();
+    }
+
+    /// Remove the entry with the maximal key and return it.
+    pub fn pop_max(&mut self) -> Option<(T, U)> {
+        Self::delete_max_internal(&mut self.root).map(|node| (node.key, node.value))
+    }
+
+    /// Unlink the node with the maximal key below `node` and return it.
+    fn delete_max_internal(mut node: &mut Link<T, U>) -> Link<T, U> {
+        while node.as_ref()?.right.is_some() {
+            let b_node = node.as_mut().unwrap();
+            b_node.n -= 1;
+            node = &mut b_node.right;
         }
+        let mut max = node.take()?;
+        *node = max.left.take();
+        Some(max)
     }
 
     /// Delete the node of `Key`.
-    pub fn delete(&mut self, key: T) {
-        self.root = Self::delete_internal(self.root.clone(), key);
-    }
-
-    fn delete_internal(
-        node: Rc<RefCell<Option<BTNode<T, U>>>>,
-        key: T,
-    ) -> Rc<RefCell<Option<BTNode<T, U>>>> {
-        match *node.clone().borrow_mut() {
-            Some(ref mut b_node) => {
-                if key < b_node.key {
-                    b_node.left = Self::delete_internal(b_node.left.clone(), key);
-                } else if key > b_node.key {
-                    b_node.right = Self::delete_internal(b_node.right.clone(), key);
-                } else {
-                    if b_node.right.borrow().is_none() {
-                        return b_node.left.clone();
-                    }
-                    if b_node.left.borrow().is_none() {
-                        return b_node.right.clone();
-                    }
-                    let new_node = Self::min_internal(b_node.right.clone());
-                    let right = Self::delete_min_internal(b_node.right.clone());
-                    if let Some(ref mut x) = *new_node.borrow_mut() {
-                        x.right = right;
-                        x.left = b_node.left.clone();
-                        x.n = Self::node_size(&x.left) + Self::node_size(&x.right) + 1;
-                        return new_node.clone();
-                    } else {
-                        unreachable!()
-                    };
-                }
-                b_node.n = Self::node_size(&b_node.left) + Self::node_size(&b_node.right) + 1;
-                node
+    pub fn delete<Q: Ord + ?Sized>(&mut self, key: &Q)
+    where
+        T: Borrow<Q>,
+    {
+        self.remove(key);
+    }
+
+    /// Remove the entry of `key` and return it, or `None` if the tree
+    /// doesn't have key `key`. Takes a single descent, counting the node
+    /// out of the subtrees above it only once it is found.
+    /// ```
+    /// # use algorithm_exercise::*;
+    /// let mut tree = BinarySearchTree::new();
+    /// tree.put(String::from("one"), 1);
+    /// assert_eq!(tree.remove("one"), Some((String::from("one"), 1)));
+    /// assert_eq!(tree.remove("one"), None);
+    /// ```
+    pub fn remove<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<(T, U)>
+    where
+        T: Borrow<Q>,
+    {
+        // the sizes of the nodes passed, to count the node out once found
+        let mut sizes = vec![];
+        let mut node = &mut self.root;
+        loop {
+            // compared before borrowing the node mutably, which would keep
+            // `node` borrowed past the loop
+            let ordering = key.cmp(node.as_ref()?.key.borrow());
+            if ordering == Ordering::Equal {
+                break;
             }
-            None => node,
+            let BTNode {
+                ref mut left,
+                ref mut right,
+                ref mut n,
+                ..
+            } = **node.as_mut().unwrap();
+            sizes.push(n);
+            node = if ordering == Ordering::Less {
+                left
+            } else {
+                right
+            };
+        }
+        for n in sizes {
+            *n -= 1;
         }
+        let mut removed = node.take()?;
+        let left = removed.left.take();
+        let mut right = removed.right.take();
+        // replace the node by its successor, the minimum of the right subtree
+        *node = match Self::delete_min_internal(&mut right) {
+            None => left,
+            Some(mut successor) => {
+                successor.left = left;
+                successor.right = right;
+                successor.n =
+                    Self::node_size(&successor.left) + Self::node_size(&successor.right) + 1;
+                Some(successor)
+            }
+        };
+        Some((removed.key, removed.value))
     }
 
-    /// Get all keys of tree.
-    pub fn all_keys(&self) -> Vec<T> {
-        if let Some(min) = self.min() {
-            if let Some(max) = self.max() {
-                return Self::keys(&self, min, max);
-            }
+    /// Number of keys in `[lo, hi]`.
+    /// ```
+    /// # use algorithm_exercise::*;
+    /// let mut tree = BinarySearchTree::new();
+    /// for k in &[1, 3, 5, 7] {
+    ///     tree.put(*k, ());
+    /// }
+    /// assert_eq!(tree.size_range(&3, &7), 3);
+    /// assert_eq!(tree.size_range(&4, &6), 1);
+    /// assert_eq!(tree.size_range(&6, &4), 0);
+    /// ```
+    pub fn size_range<Q: Ord + ?Sized>(&self, lo: &Q, hi: &Q) -> usize
+    where
+        T: Borrow<Q>,
+    {
+        if hi < lo {
+            0
+        } else {
+            self.rank_of_key(hi) - self.rank_of_key(lo) + usize::from(self.contains(hi))
         }
-        vec![]
     }
-    /// Get keys of tree within a range.
-    pub fn keys(&self, lo: T, hi: T) -> Vec<T> {
-        let mut vec: Vec<T> = vec![];
-        Self::keys_internal(&self.root, &mut vec, lo, hi);
-        vec
+
+    /// Get all keys of tree.
+    pub fn all_keys(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.keys().cloned().collect()
     }
 
-    fn keys_internal(node: &Rc<RefCell<Option<BTNode<T, U>>>>, vec: &mut Vec<T>, lo: T, hi: T) {
-        match *node.borrow() {
-            None => return,
-            Some(ref b_node) => {
-                if lo < b_node.key {
-                    Self::keys_internal(&b_node.left, vec, lo, hi)
-                }
-                if lo <= b_node.key && hi >= b_node.key {
-                    vec.push(b_node.key)
-                }
-                if hi > b_node.key {
-                    Self::keys_internal(&b_node.right, vec, lo, hi)
+    /// Iterate over the key value pairs in order of keys. The iterator is
+    /// lazy and double-ended: `iter().rev()` starts from the largest key.
+    /// ```
+    /// # use algorithm_exercise::*;
+    /// let mut tree = BinarySearchTree::new();
+    /// tree.put(2, "two");
+    /// tree.put(1, "one");
+    /// tree.put(3, "three");
+    /// assert_eq!(tree.iter().next(), Some((&1, &"one")));
+    /// assert_eq!(tree.iter().rev().next(), Some((&3, &"three")));
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T, U> {
+        self.range::<T, _>(..)
+    }
+
+    /// Iterate over the key value pairs with keys in `range`, in order.
+    /// ```
+    /// # use algorithm_exercise::*;
+    /// let mut tree = BinarySearchTree::new();
+    /// for k in 0..10 {
+    ///     tree.put(k, k * k);
+    /// }
+    /// let squares: Vec<_> = tree.range(3..6).map(|(_, v)| *v).collect();
+    /// assert_eq!(squares, [9, 16, 25]);
+    /// assert_eq!(tree.range(..=8).rev().next(), Some((&8, &64)));
+    /// ```
+    pub fn range<Q, R>(&self, range: R) -> Iter<'_, T, U>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        Iter(tree::Iter::new(self.root.as_deref(), range))
+    }
+
+    /// Iterate over the keys in order.
+    pub fn keys(&self) -> Keys<'_, T, U> {
+        tree::Keys(self.iter())
+    }
+
+    /// Iterate over the values in order of keys.
+    pub fn values(&self) -> Values<'_, T, U> {
+        tree::Values(self.iter())
+    }
+
+    /// Number of levels of the tree, i.e. of nodes on the longest path
+    /// down from the root; 0 for an empty tree.
+    pub fn height(&self) -> usize {
+        tree::height(self.root.as_deref())
+    }
+
+    /// Average depth of a node, the root being at depth 0: the internal
+    /// path length divided by the number of nodes. 0 for an empty tree.
+    /// ```
+    /// # use algorithm_exercise::*;
+    /// let mut tree = BinarySearchTree::new();
+    /// for k in &[2, 1, 3] {
+    ///     tree.put(*k, ());
+    /// }
+    /// assert_eq!(tree.height(), 2);
+    /// assert_eq!(tree.average_depth(), 2.0 / 3.0);
+    /// assert_eq!(tree.depth_histogram(), [1, 2]);
+    /// assert_eq!(tree.leaf_count(), 2);
+    /// ```
+    pub fn average_depth(&self) -> f64 {
+        tree::average_depth(self.root.as_deref())
+    }
+
+    /// Number of nodes at each depth, from the root down.
+    pub fn depth_histogram(&self) -> Vec<usize> {
+        tree::depth_histogram(self.root.as_deref())
+    }
+
+    /// Number of nodes without children.
+    pub fn leaf_count(&self) -> usize {
+        tree::leaf_count(self.root.as_deref())
+    }
+
+    /// The tree in Graphviz DOT, each node showing its key, value and
+    /// subtree count. Render it with e.g. `dot -Tsvg`.
+    /// ```
+    /// # use algorithm_exercise::*;
+    /// let mut tree = BinarySearchTree::new();
+    /// tree.put(2, "two");
+    /// tree.put(1, "one");
+    /// assert!(tree.to_dot().contains("n0 -> n1"));
+    /// ```
+    pub fn to_dot(&self) -> String
+    where
+        T: fmt::Display,
+        U: fmt::Display,
+    {
+        render::dot(&self.drawing())
+    }
+
+    /// The tree as an SVG picture laid out without Graphviz: keys left to
+    /// right in order, one row per level. Hovering over a node shows
+    /// its value and subtree count.
+    pub fn to_svg(&self) -> String
+    where
+        T: fmt::Display,
+        U: fmt::Display,
+    {
+        render::svg(&self.drawing())
+    }
+
+    fn drawing(&self) -> Vec<Drawn>
+    where
+        T: fmt::Display,
+        U: fmt::Display,
+    {
+        render::drawing(self.root.as_deref(), |_| false)
+    }
+
+    /// Check the invariants of the tree: keys in symmetric order, and
+    /// subtree counts matching the subtree sizes and giving each key its
+    /// rank. Degenerate trees are checked without deep recursion.
+    /// ```
+    /// # use algorithm_exercise::*;
+    /// let mut tree = BinarySearchTree::new();
+    /// tree.put(2, "two");
+    /// tree.put(1, "one");
+    /// assert!(tree.check().is_ok());
+    /// ```
+    pub fn check(&self) -> Report {
+        let mut report = Report::default();
+        tree::check(
+            self.root.as_deref(),
+            &mut report.violations,
+            |_, _, (), (), _| (),
+        );
+        report
+    }
+}
+
+impl<T: Ord, U> Default for BinarySearchTree<T, U> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, U> Drop for BinarySearchTree<T, U> {
+    /// Free the nodes one at a time, rotating left children up, so that
+    /// dropping a degenerate tree doesn't recurse down its whole height.
+    fn drop(&mut self) {
+        let mut node = self.root.take();
+        while let Some(mut b_node) = node {
+            node = match b_node.left.take() {
+                Some(mut left) => {
+                    b_node.left = left.right.take();
+                    left.right = Some(b_node);
+                    Some(left)
                 }
-            }
+                None => b_node.right.take(),
+            };
         }
     }
 }
 
+impl<T: Ord + fmt::Debug, U: fmt::Debug> fmt::Debug for BinarySearchTree<T, U> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T: Ord, U> IntoIterator for &'a BinarySearchTree<T, U> {
+    type Item = (&'a T, &'a U);
+    type IntoIter = Iter<'a, T, U>;
+
+    fn into_iter(self) -> Iter<'a, T, U> {
+        self.iter()
+    }
+}
+
+impl<T: Ord, U> OrderedSymbolTable<T, U> for BinarySearchTree<T, U> {
+    fn put(&mut self, key: T, value: U) -> Option<U> {
+        self.put(key, value)
+    }
+
+    fn get<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&U>
+    where
+        T: Borrow<Q>,
+    {
+        self.get(key)
+    }
+
+    fn delete<Q: Ord + ?Sized>(&mut self, key: &Q)
+    where
+        T: Borrow<Q>,
+    {
+        self.delete(key)
+    }
+
+    fn size(&self) -> usize {
+        self.size()
+    }
+
+    fn min(&self) -> Option<&T> {
+        self.min()
+    }
+
+    fn max(&self) -> Option<&T> {
+        self.max()
+    }
+
+    fn floor<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+    {
+        self.floor(key)
+    }
+
+    fn ceiling<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+    {
+        self.ceiling(key)
+    }
+
+    fn rank<Q: Ord + ?Sized>(&self, key: &Q) -> usize
+    where
+        T: Borrow<Q>,
+    {
+        self.rank_of_key(key)
+    }
+
+    fn select(&self, rank: usize) -> Option<&T> {
+        self.key_of_rank(rank)
+    }
+
+    fn keys<'a, Q: Ord + ?Sized>(&'a self, lo: &Q, hi: &Q) -> Box<dyn Iterator<Item = &'a T> + 'a>
+    where
+        T: Borrow<Q>,
+    {
+        Box::new(tree::Keys(
+            self.range((Bound::Included(lo), Bound::Included(hi))),
+        ))
+    }
+}
+
+/// Entry of a key in a `BinarySearchTree`, created by `entry`.
+pub enum Entry<'a, T, U> {
+    Occupied(OccupiedEntry<'a, T, U>),
+    Vacant(VacantEntry<'a, T, U>),
+}
+
+/// Entry of a key in the tree.
+pub struct OccupiedEntry<'a, T, U> {
+    node: &'a mut BTNode<T, U>,
+}
+
+/// Entry of a key not in the tree.
+pub struct VacantEntry<'a, T, U> {
+    key: T,
+    /// Where the node of `key` goes.
+    slot: &'a mut Link<T, U>,
+    /// Sizes of the nodes above `slot`.
+    sizes: Vec<&'a mut usize>,
+}
+
+impl<'a, T: Ord, U> Entry<'a, T, U> {
+    /// The key of the entry.
+    pub fn key(&self) -> &T {
+        match *self {
+            Entry::Occupied(ref entry) => entry.key(),
+            Entry::Vacant(ref entry) => entry.key(),
+        }
+    }
+
+    /// The value of the entry, inserting `default` if it is vacant.
+    pub fn or_insert(self, default: U) -> &'a mut U {
+        self.or_insert_with(|| default)
+    }
+
+    /// The value of the entry, inserting the result of `default` if it is
+    /// vacant.
+    pub fn or_insert_with<F: FnOnce() -> U>(self, default: F) -> &'a mut U {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// The value of the entry, inserting `U::default()` if it is vacant.
+    pub fn or_default(self) -> &'a mut U
+    where
+        U: Default,
+    {
+        self.or_insert_with(U::default)
+    }
+
+    /// Apply `f` to the value of the entry if it is occupied.
+    /// ```
+    /// # use algorithm_exercise::*;
+    /// let mut tree = BinarySearchTree::new();
+    /// tree.entry("a").and_modify(|v| *v += 1).or_insert(1);
+    /// tree.entry("a").and_modify(|v| *v += 1).or_insert(1);
+    /// assert_eq!(tree.get("a"), Some(&2));
+    /// ```
+    pub fn and_modify<F: FnOnce(&mut U)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(ref mut entry) = self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, T: Ord, U> OccupiedEntry<'a, T, U> {
+    /// The key of the entry.
+    pub fn key(&self) -> &T {
+        &self.node.key
+    }
+
+    /// The value of the entry.
+    pub fn get(&self) -> &U {
+        &self.node.value
+    }
+
+    /// The value of the entry, mutably.
+    pub fn get_mut(&mut self) -> &mut U {
+        &mut self.node.value
+    }
+
+    /// The value of the entry, borrowed for as long as the tree.
+    pub fn into_mut(self) -> &'a mut U {
+        &mut self.node.value
+    }
+
+    /// Replace the value of the entry, returning the old one.
+    pub fn insert(&mut self, value: U) -> U {
+        mem::replace(&mut self.node.value, value)
+    }
+}
+
+impl<'a, T: Ord, U> VacantEntry<'a, T, U> {
+    /// The key of the entry.
+    pub fn key(&self) -> &T {
+        &self.key
+    }
+
+    /// Insert the key of the entry with `value`.
+    pub fn insert(self, value: U) -> &'a mut U {
+        for n in self.sizes {
+            *n += 1;
+        }
+        let node = self.slot.insert(Box::new(BTNode::new(self.key, value, 1)));
+        &mut node.value
+    }
+}
+
+/// Iterator over the key value pairs of a `BinarySearchTree`, created by
+/// `iter` and `range`.
+pub struct Iter<'a, T, U>(tree::Iter<'a, BTNode<T, U>>);
+
+impl<'a, T, U> Iterator for Iter<'a, T, U> {
+    type Item = (&'a T, &'a U);
+
+    fn next(&mut self) -> Option<(&'a T, &'a U)> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, T, U> DoubleEndedIterator for Iter<'a, T, U> {
+    fn next_back(&mut self) -> Option<(&'a T, &'a U)> {
+        self.0.next_back()
+    }
+}
+
+impl<T, U> ExactSizeIterator for Iter<'_, T, U> {}
+
+/// Iterator over the keys of a `BinarySearchTree`, created by `keys`.
+pub type Keys<'a, T, U> = tree::Keys<Iter<'a, T, U>>;
+
+/// Iterator over the values of a `BinarySearchTree`, created by `values`.
+pub type Values<'a, T, U> = tree::Values<Iter<'a, T, U>>;
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::invariants::Violation;
+    use crate::random;
+    use crate::symbol_table::tests::random_changes;
+    use alloc::format;
+    use alloc::string::String;
 
     #[test]
     fn test() {
@@ -359,17 +927,258 @@ mod tests {
         table.put(4, "4");
         table.put(2, "2");
         assert_eq!(table.size(), 3);
-        assert_eq!(table.get(3), Some("3"));
-        assert_eq!(table.get(1), None);
-        assert_eq!(table.floor(5), Some(4));
-        assert_eq!(table.key_of_rank(2), Some(4));
-        assert_eq!(table.rank_of_key(4), 2);
+        assert_eq!(table.get(&3), Some(&"3"));
+        assert_eq!(table.get(&1), None);
+        assert_eq!(table.floor(&5), Some(&4));
+        assert_eq!(table.key_of_rank(2), Some(&4));
+        assert_eq!(table.rank_of_key(&4), 2);
         // table.delete_min();
-        table.delete(3);
+        table.delete(&3);
         assert_eq!(table.size(), 2);
-        assert_eq!(table.get(3), None);
-        assert_eq!(table.floor(4), Some(4));
-        assert_eq!(table.floor(3), Some(2));
+        assert_eq!(table.get(&3), None);
+        assert_eq!(table.floor(&4), Some(&4));
+        assert_eq!(table.floor(&3), Some(&2));
         assert_eq!(table.all_keys(), vec![2, 4]);
+        assert_eq!(table.ceiling(&3), Some(&4));
+        assert_eq!(table.successor(&2), Some(&4));
+        assert_eq!(table.successor(&4), None);
+        assert_eq!(table.predecessor(&4), Some(&2));
+        assert_eq!(table.predecessor(&2), None);
+        assert_eq!(table.predecessor(&3), Some(&2));
+        assert!(table.contains(&4) && !table.contains(&3));
+        assert_eq!(table.size_range(&2, &4), 2);
+        assert_eq!(format!("{:?}", table), r#"{2: "2", 4: "4"}"#);
+        table.delete_max();
+        assert_eq!(table.all_keys(), vec![2]);
+        table.delete_max();
+        assert!(table.is_empty());
+        table.delete_max();
+        assert_eq!(table.ceiling(&0), None);
+
+        let mut table = BinarySearchTree::new();
+        for k in [5, 2, 8, 1, 3, 7, 9, 4, 6, 0].iter() {
+            table.put(*k, k * 10);
+        }
+        assert_eq!(
+            table.keys().cloned().collect::<Vec<_>>(),
+            (0..10).collect::<Vec<_>>()
+        );
+        assert_eq!(table.values().next_back(), Some(&90));
+        let mut range = table.range(3..=6);
+        assert_eq!(range.len(), 4);
+        assert_eq!(range.next(), Some((&3, &30)));
+        assert_eq!(range.next_back(), Some((&6, &60)));
+        assert_eq!(range.collect::<Vec<_>>(), [(&4, &40), (&5, &50)]);
+        let excluded = (Bound::Excluded(2), Bound::Excluded(5));
+        assert_eq!(
+            table.range(excluded).rev().collect::<Vec<_>>(),
+            [(&4, &40), (&3, &30)]
+        );
+        assert_eq!(table.range(20..).next(), None);
+        assert_eq!(
+            table
+                .range((Bound::Included(6), Bound::Excluded(3)))
+                .next_back(),
+            None
+        );
+        table.delete(&4);
+        assert_eq!(table.range(4..=4).len(), 0);
+        let mut sum = 0;
+        for (k, v) in &table {
+            sum += k + v;
+        }
+        assert_eq!(sum, 11 * (45 - 4));
+
+        // owned keys, looked up by a borrowed form
+        let mut table = BinarySearchTree::new();
+        for word in "the quick brown fox jumps over the lazy dog".split(' ') {
+            *table.entry(String::from(word)).or_default() += 1;
+        }
+        assert_eq!(table.size(), 8);
+        assert_eq!(table.key_of_rank(7).map(String::as_str), Some("the"));
+        assert!(table.update("fox", |n| *n += 10));
+        *table.get_mut("dog").unwrap() = 5;
+        assert_eq!(table.values().sum::<i32>(), 9 + 10 + 4);
+        match table.entry(String::from("fox")) {
+            Entry::Occupied(mut entry) => assert_eq!(entry.insert(0), 11),
+            Entry::Vacant(_) => unreachable!(),
+        }
+        assert_eq!(table.entry(String::from("cat")).key(), "cat");
+        assert!(!table.contains("cat"));
+        assert_eq!(table.size(), 8);
+        assert!(table.check().is_ok());
+        assert_eq!(table.get("the"), Some(&2));
+        assert_eq!(table.floor("p").map(String::as_str), Some("over"));
+        let (lo, hi) = (Bound::Included("l"), Bound::Excluded("q"));
+        assert_eq!(table.range::<str, _>((lo, hi)).count(), 2);
+        table.delete("the");
+        assert!(!table.contains("the"));
+        assert_eq!(table.put(String::from("dog"), 1), Some(5));
+        assert_eq!(table.remove("fox"), Some((String::from("fox"), 0)));
+        assert_eq!(table.remove("fox"), None);
+        assert_eq!(table.pop_max(), Some((String::from("quick"), 1)));
+        assert_eq!(table.pop_min(), Some((String::from("brown"), 1)));
+        assert_eq!(table.size(), 4);
+
+        // a random tree is a little over 1.39 lg n deep on average, counting
+        // the root as 1; lg 10000 = 13.3
+        let mut keys: Vec<u32> = (0..10_000).collect();
+        random::shuffle(&mut keys, &mut random::seeded(49));
+        let mut table = BinarySearchTree::new();
+        for &k in &keys {
+            table.put(k, ());
+        }
+        let average = table.average_depth() + 1.0;
+        assert!(
+            average > 1.39 * 13.3 - 3.0 && average < 1.39 * 13.3,
+            "{}",
+            average
+        );
+        assert!(table.height() > 2 * 14);
+        let histogram = table.depth_histogram();
+        assert_eq!(histogram.len(), table.height());
+        assert_eq!(histogram.iter().sum::<usize>(), 10_000);
+        assert_eq!(histogram[..3], [1, 2, 4]);
+        assert!(table.leaf_count() > 10_000 / 4);
+        assert_eq!(BinarySearchTree::<u32, ()>::new().average_depth(), 0.0);
+
+        // drawings of a tree with keys in symmetric order
+        let mut table = BinarySearchTree::new();
+        for k in &[2, 1, 3] {
+            table.put(*k, k * 10);
+        }
+        let dot = table.to_dot();
+        assert!(dot.contains("n0 [label=\"2\\n20\\nn = 3\"];"), "{}", dot);
+        assert!(dot.contains("n0 -> n1;\n    n0 -> n2;"), "{}", dot);
+        assert!(!dot.contains("red"));
+        let svg = table.to_svg();
+        assert_eq!(svg.matches("<line").count(), 2);
+        assert!(svg.contains("<circle cx=\"20\" cy=\"90\""), "{}", svg);
+        assert_eq!(
+            BinarySearchTree::<u32, u32>::new().to_dot(),
+            "digraph {\n    graph [ordering=out];\n    node [shape=box, style=rounded];\n}\n"
+        );
+
+        // a broken tree is reported
+        let mut table = BinarySearchTree::new();
+        for k in &[2, 1, 3] {
+            table.put(*k, ());
+        }
+        assert!(table.check().is_ok());
+        table.root.as_mut().unwrap().n = 5;
+        assert_eq!(
+            table.check().violations,
+            [Violation::Count {
+                node: 1,
+                n: 5,
+                actual: 3
+            }]
+        );
+        table.root.as_mut().unwrap().n = 3;
+        table.root.as_mut().unwrap().left.as_mut().unwrap().n = 2;
+        assert_eq!(
+            table.check().violations,
+            [
+                Violation::Count {
+                    node: 0,
+                    n: 2,
+                    actual: 1
+                },
+                Violation::Rank { node: 1, rank: 2 },
+                Violation::Rank { node: 2, rank: 3 },
+            ]
+        );
+        table.root.as_mut().unwrap().left.as_mut().unwrap().n = 1;
+        table.root.as_mut().unwrap().left.as_mut().unwrap().key = 4;
+        assert_eq!(
+            table.check().violations,
+            [Violation::SymmetricOrder { node: 1 }]
+        );
+
+        // random changes keep the invariants
+        let mut table = BinarySearchTree::new();
+        let model = random_changes(&mut table, 3, 2000, 100, |table, _| {
+            assert!(table.check().is_ok(), "{}", table.check());
+        });
+        assert!(table.iter().eq(model.iter()));
+    }
+
+    /// Sorted keys build a tree as tall as it is big; exercise every
+    /// operation on one.
+    fn sorted_keys(n: u32) {
+        let mut table = BinarySearchTree::new();
+        for k in 0..n {
+            table.put(k, k);
+        }
+        let last = n - 1;
+        assert_eq!(table.size(), n as usize);
+        assert_eq!(table.height(), n as usize);
+        assert_eq!(table.average_depth(), f64::from(last) / 2.0);
+        assert_eq!(table.leaf_count(), 1);
+        assert_eq!(table.put(last, 0), Some(last));
+        assert_eq!(table.get(&last), Some(&0));
+        *table.get_mut(&0).unwrap() = 1;
+        assert!(table.contains(&(n / 2)));
+        assert_eq!(table.min(), Some(&0));
+        assert_eq!(table.max(), Some(&last));
+        assert_eq!(table.floor(&n), Some(&last));
+        assert_eq!(table.ceiling(&last), Some(&last));
+        assert_eq!(table.rank_of_key(&last), last as usize);
+        assert_eq!(table.key_of_rank(last as usize), Some(&last));
+        assert_eq!(table.iter().rev().len(), n as usize);
+        assert_eq!(table.remove(&(last - 1)), Some((last - 1, last - 1)));
+        assert_eq!(table.pop_max(), Some((last, 0)));
+        assert_eq!(table.pop_min(), Some((0, 1)));
+        assert_eq!(table.size(), n as usize - 3);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn degenerate() {
+        // a stack far too small to recurse down 5000 nodes
+        std::thread::Builder::new()
+            .stack_size(64 * 1024)
+            .spawn(|| {
+                sorted_keys(5000);
+                let mut table = BinarySearchTree::new();
+                for k in (0..5000).rev() {
+                    table.put(k, ());
+                }
+                assert!(table.check().is_ok());
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    /// Inserts the keys one by one, taking about n²/2 comparisons. Run it
+    /// with `cargo test --release -- --ignored`.
+    #[test]
+    #[ignore]
+    fn sorted_million() {
+        sorted_keys(1_000_000);
+    }
+
+    #[test]
+    fn degenerate_million() {
+        // the chain that putting sorted keys builds, made in linear time
+        let n = 1_000_000;
+        let mut root: Link<u32, u32> = None;
+        for k in (0..n).rev() {
+            let mut b_node = BTNode::new(k, k, (n - k) as usize);
+            b_node.right = root.take();
+            root = Some(Box::new(b_node));
+        }
+        let mut table = BinarySearchTree { root };
+        assert!(table.check().is_ok());
+        assert_eq!(table.height(), n as usize);
+        assert!(table.iter().map(|(k, _)| *k).eq(0..n));
+        assert!(table.keys().rev().copied().eq((0..n).rev()));
+        assert_eq!(table.put(n, n), None);
+        assert_eq!(table.put(n, 0), Some(n));
+        assert_eq!(table.remove(&(n + 1)), None);
+        assert_eq!(table.remove(&(n - 1)), Some((n - 1, n - 1)));
+        assert_eq!(table.rank_of_key(&n), n as usize - 1);
+        assert!(table.check().is_ok());
     }
 }
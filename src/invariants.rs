@@ -0,0 +1,129 @@
+//! Structural checks for the search trees.
+//!
+//! `BinarySearchTree::check` and `BalancedSearchTree::check` walk the
+//! whole tree and collect every broken invariant into a `Report`. They
+//! take linear time, so they're meant for tests and debug builds:
+//! ```
+//! # use algorithm_exercise::*;
+//! let mut tree = BalancedSearchTree::new();
+//! for k in 0..100 {
+//!     tree.put(k, ());
+//!     debug_assert!(tree.check().is_ok(), "{}", tree.check());
+//! }
+//! ```
+use alloc::vec::Vec;
+use core::fmt;
+
+/// A broken invariant. Nodes are named by their position in key order,
+/// as found by an in-order walk.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Violation {
+    /// The key of `node` isn't greater than the key before it.
+    SymmetricOrder { node: usize },
+    /// The subtree count of `node` is `n` but the subtree has `actual`
+    /// nodes.
+    Count {
+        node: usize,
+        n: usize,
+        actual: usize,
+    },
+    /// Going by the subtree counts, as `rank_of_key` and `key_of_rank`
+    /// do, `node` has rank `rank`.
+    Rank { node: usize, rank: usize },
+    /// `node` and its left child are both red.
+    TwoReds { node: usize },
+    /// The right child of `node` is red.
+    RightLeaningRed { node: usize },
+    /// The paths below `node` don't cross the same number of black
+    /// links: `left` on the left, `right` on the right.
+    BlackHeight {
+        node: usize,
+        left: usize,
+        right: usize,
+    },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Violation::SymmetricOrder { node } => {
+                write!(f, "node {} is out of order", node)
+            }
+            Violation::Count { node, n, actual } => {
+                write!(f, "node {} counts {} nodes, has {}", node, n, actual)
+            }
+            Violation::Rank { node, rank } => {
+                write!(f, "node {} is counted as rank {}", node, rank)
+            }
+            Violation::TwoReds { node } => write!(f, "node {} has two red links", node),
+            Violation::RightLeaningRed { node } => {
+                write!(f, "node {} has a right-leaning red link", node)
+            }
+            Violation::BlackHeight { node, left, right } => write!(
+                f,
+                "node {} has black height {} on the left, {} on the right",
+                node, left, right
+            ),
+        }
+    }
+}
+
+/// The violations found by a check, in the order found.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Report {
+    pub violations: Vec<Violation>,
+}
+
+impl Report {
+    /// Whether no invariant is broken.
+    pub fn is_ok(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_ok() {
+            return write!(f, "no violations");
+        }
+        for (i, violation) in self.violations.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{}", violation)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    #[test]
+    fn test() {
+        let mut report = Report::default();
+        assert!(report.is_ok());
+        assert_eq!(report.to_string(), "no violations");
+        report.violations = vec![Violation::Rank { node: 2, rank: 1 }];
+        assert_eq!(report.to_string(), "node 2 is counted as rank 1");
+        report.violations = vec![
+            Violation::Count {
+                node: 0,
+                n: 3,
+                actual: 1,
+            },
+            Violation::BlackHeight {
+                node: 1,
+                left: 2,
+                right: 1,
+            },
+        ];
+        assert_eq!(
+            report.to_string(),
+            "node 0 counts 3 nodes, has 1; node 1 has black height 2 on the left, 1 on the right"
+        );
+    }
+}
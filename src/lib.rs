@@ -1,9 +1,34 @@
+#![no_std]
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
 mod sorting;
-mod binary_search_tree;
-// mod balanced_search_tree;
-mod test;
+mod network;
+mod merge;
+mod sort_spec;
+mod sort_ext;
+pub mod binary_search_tree;
+pub mod arena_search_tree;
+pub mod balanced_search_tree;
+mod sorted_array_table;
+pub mod symbol_table;
+pub mod invariants;
+mod render;
+mod tree;
+pub mod verify;
+pub mod total_order;
+pub mod search;
+pub mod random;
 
 pub use self::sorting::Sorting;
+pub use self::sort_ext::{SortExt, SortedIterator};
+pub use self::merge::KMerge;
+pub use self::sort_spec::{BySpec, Nulls, SortSpec};
+pub use self::total_order::Incomparable;
 pub use self::binary_search_tree::BinarySearchTree;
-// pub use self::balanced_search_tree::BalancedSearchTree;
-pub use self::test::BalancedSearchTree;
\ No newline at end of file
+pub use self::arena_search_tree::ArenaSearchTree;
+pub use self::balanced_search_tree::BalancedSearchTree;
+pub use self::sorted_array_table::SortedArrayTable;
+pub use self::symbol_table::OrderedSymbolTable;
@@ -0,0 +1,195 @@
+//! K-way merge of sorted inputs into one sorted stream.
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+/// Merge of any number of sorted inputs into one sorted stream.
+///
+/// The inputs are kept in a binary min-heap keyed by their next item, so
+/// each item costs O(log k) comparisons for k inputs. Equal items come out
+/// in the order of their inputs, and in input order within an input.
+/// ```
+/// # use algorithm_exercise::*;
+/// let shards = vec![vec![1, 4, 7], vec![2, 4, 8], vec![0, 9]];
+/// let merged: Vec<_> = KMerge::new(shards.clone()).collect();
+/// assert_eq!(merged, [0, 1, 2, 4, 4, 7, 8, 9]);
+/// let merged: Vec<_> = KMerge::dedup(shards).collect();
+/// assert_eq!(merged, [0, 1, 2, 4, 7, 8, 9]);
+/// ```
+pub struct KMerge<I: Iterator> {
+    inputs: Vec<I>,
+    /// next item of each non-empty input, with the input's index
+    heap: Vec<(I::Item, usize)>,
+    dedup: bool,
+}
+
+impl<I> KMerge<I>
+where
+    I: Iterator,
+    I::Item: PartialOrd,
+{
+    /// Merge `inputs`, keeping every item.
+    pub fn new<S>(inputs: S) -> Self
+    where
+        S: IntoIterator,
+        S::Item: IntoIterator<IntoIter = I, Item = I::Item>,
+    {
+        let mut inputs: Vec<I> = inputs.into_iter().map(|i| i.into_iter()).collect();
+        let mut merge = KMerge {
+            heap: Vec::with_capacity(inputs.len()),
+            inputs: vec![],
+            dedup: false,
+        };
+        for (i, input) in inputs.iter_mut().enumerate() {
+            if let Some(item) = input.next() {
+                merge.push(item, i);
+            }
+        }
+        merge.inputs = inputs;
+        merge
+    }
+
+    /// Merge `inputs`, yielding only the first of a run of equal items.
+    pub fn dedup<S>(inputs: S) -> Self
+    where
+        S: IntoIterator,
+        S::Item: IntoIterator<IntoIter = I, Item = I::Item>,
+    {
+        KMerge {
+            dedup: true,
+            ..Self::new(inputs)
+        }
+    }
+
+    // earlier inputs win ties, which keeps the merge stable
+    fn less(heap: &[(I::Item, usize)], a: usize, b: usize) -> bool {
+        let (ref x, i) = heap[a];
+        let (ref y, j) = heap[b];
+        match x.partial_cmp(y) {
+            Some(Ordering::Less) => true,
+            Some(Ordering::Greater) => false,
+            _ => i < j,
+        }
+    }
+
+    fn push(&mut self, item: I::Item, input: usize) {
+        self.heap.push((item, input));
+        let mut k = self.heap.len() - 1;
+        while k > 0 && Self::less(&self.heap, k, (k - 1) / 2) {
+            self.heap.swap(k, (k - 1) / 2);
+            k = (k - 1) / 2;
+        }
+    }
+
+    fn pop(&mut self) -> Option<(I::Item, usize)> {
+        if self.heap.is_empty() {
+            return None;
+        }
+        let top = self.heap.swap_remove(0);
+        let mut k = 0;
+        loop {
+            let mut child = 2 * k + 1;
+            if child >= self.heap.len() {
+                break;
+            }
+            if child + 1 < self.heap.len() && Self::less(&self.heap, child + 1, child) {
+                child += 1;
+            }
+            if !Self::less(&self.heap, child, k) {
+                break;
+            }
+            self.heap.swap(k, child);
+            k = child;
+        }
+        Some(top)
+    }
+
+    // take the next item of the input that `top` came from
+    fn pop_and_refill(&mut self) -> Option<I::Item> {
+        let (item, input) = self.pop()?;
+        if let Some(next) = self.inputs[input].next() {
+            self.push(next, input);
+        }
+        Some(item)
+    }
+}
+
+impl<I> Iterator for KMerge<I>
+where
+    I: Iterator,
+    I::Item: PartialOrd,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        let item = self.pop_and_refill()?;
+        if self.dedup {
+            while matches!(self.heap.first(), Some((head, _)) if *head == item) {
+                self.pop_and_refill();
+            }
+        }
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let mut lo = self.heap.len();
+        let mut hi = Some(self.heap.len());
+        for input in &self.inputs {
+            let (l, h) = input.size_hint();
+            lo = lo.saturating_add(l);
+            hi = hi.and_then(|hi| hi.checked_add(h?));
+        }
+        if self.dedup {
+            lo = lo.min(1);
+        }
+        (lo, hi)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verify;
+
+    #[test]
+    fn test() {
+        let a = [(1, 'a'), (3, 'a'), (3, 'b'), (5, 'a')];
+        let b = [(0, 'b'), (3, 'c'), (6, 'b')];
+        let c = [(3, 'd')];
+        let key = |r: &(i32, char)| r.0;
+
+        let merged: Vec<_> = KMerge::new(vec![
+            a.iter().map(key),
+            b.iter().map(key),
+            c.iter().map(key),
+        ])
+        .collect();
+        assert_eq!(merged, [0, 1, 3, 3, 3, 3, 5, 6]);
+        assert!(verify::is_sorted(&merged));
+
+        // stable: ties come out by input, then by position within input
+        fn tagged(
+            s: &[(i32, char)],
+            input: usize,
+        ) -> impl Iterator<Item = verify::Tagged<i32>> + '_ {
+            s.iter().enumerate().map(move |(i, r)| verify::Tagged {
+                value: r.0,
+                position: input * 10 + i,
+            })
+        }
+        let merged: Vec<_> =
+            KMerge::new(vec![tagged(&a, 0), tagged(&b, 1), tagged(&c, 2)]).collect();
+        let threes: Vec<_> = merged
+            .iter()
+            .filter(|t| t.value == 3)
+            .map(|t| t.position)
+            .collect();
+        assert_eq!(threes, [1, 2, 11, 20]);
+
+        let merged: Vec<_> = KMerge::dedup(vec![&[1, 1, 2][..], &[1, 2, 3], &[], &[3]]).collect();
+        assert_eq!(merged, [&1, &2, &3]);
+        let merged = KMerge::new(vec![&[1, 1, 2][..], &[1, 2, 3], &[], &[3]]);
+        assert_eq!(merged.size_hint(), (7, Some(7)));
+        assert_eq!(KMerge::new(Vec::<Vec<i32>>::new()).next(), None);
+    }
+}
@@ -0,0 +1,217 @@
+//! Randomization primitives: shuffling and sampling.
+//!
+//! Every function takes the random number generator to use, so results
+//! are reproducible when it is seeded, e.g. with `seeded`.
+#[cfg(feature = "std")]
+use alloc::collections::BinaryHeap;
+#[cfg(feature = "std")]
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use core::cmp::Ordering;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// A random number generator that produces the same numbers for the same
+/// `seed`.
+pub fn seeded(seed: u64) -> StdRng {
+    StdRng::seed_from_u64(seed)
+}
+
+/// Knuth (Fisher-Yates) shuffle: every permutation of `keys` is
+/// equally likely.
+/// ```
+/// # use algorithm_exercise::random;
+/// let mut a: Vec<u32> = (0..10).collect();
+/// let mut b = a.clone();
+/// random::shuffle(&mut a, &mut random::seeded(7));
+/// random::shuffle(&mut b, &mut random::seeded(7));
+/// assert_eq!(a, b);
+/// ```
+pub fn shuffle<T, R: Rng + ?Sized>(keys: &mut [T], rng: &mut R) {
+    let n = keys.len();
+    partial_shuffle(keys, n, rng);
+}
+
+/// Shuffle just enough of `keys` that the first `k` of them are a
+/// uniformly random sample, in random order. The rest are left in
+/// unspecified order.
+pub fn partial_shuffle<T, R: Rng + ?Sized>(keys: &mut [T], k: usize, rng: &mut R) {
+    let n = keys.len();
+    for i in 0..k.min(n) {
+        let r = rng.gen_range(i, n);
+        keys.swap(i, r);
+    }
+}
+
+/// Choose `k` items of `items` uniformly at random in a single pass
+/// (Vitter's algorithm R). Returns all items if there are fewer than `k`.
+/// ```
+/// # use algorithm_exercise::random;
+/// let sample = random::reservoir_sample(0..1000, 5, &mut random::seeded(1));
+/// assert_eq!(sample.len(), 5);
+/// ```
+pub fn reservoir_sample<I, R>(items: I, k: usize, rng: &mut R) -> Vec<I::Item>
+where
+    I: IntoIterator,
+    R: Rng + ?Sized,
+{
+    let mut reservoir = Vec::with_capacity(k);
+    for (i, item) in items.into_iter().enumerate() {
+        if i < k {
+            reservoir.push(item);
+        } else {
+            let r = rng.gen_range(0, i + 1);
+            if r < k {
+                reservoir[r] = item;
+            }
+        }
+    }
+    reservoir
+}
+
+/// Index chosen with probability proportional to its weight, or `None`
+/// if no weight is positive. Weights that aren't positive numbers are
+/// never chosen.
+/// ```
+/// # use algorithm_exercise::random;
+/// let i = random::weighted_choice(&[0.0, 2.0, 0.0], &mut random::seeded(3));
+/// assert_eq!(i, Some(1));
+/// ```
+pub fn weighted_choice<R: Rng + ?Sized>(weights: &[f64], rng: &mut R) -> Option<usize> {
+    let usable = |w: f64| w > 0.0 && w.is_finite();
+    let total: f64 = weights.iter().cloned().filter(|&w| usable(w)).sum();
+    if total <= 0.0 {
+        return None;
+    }
+    let mut x = rng.gen_range(0.0, total);
+    let mut last = None;
+    for (i, &w) in weights.iter().enumerate() {
+        if usable(w) {
+            if x < w {
+                return Some(i);
+            }
+            x -= w;
+            last = Some(i);
+        }
+    }
+    // rounding can leave `x` just past the last weight
+    last
+}
+
+/// Choose `k` of the `(item, weight)` pairs without replacement, each
+/// draw picking an item with probability proportional to its weight, in
+/// a single pass (Efraimidis and Spirakis' algorithm A-Res). Items whose
+/// weight isn't a positive number are never chosen.
+/// ```
+/// # use algorithm_exercise::random;
+/// let items = vec![("a", 1.0), ("b", 0.0), ("c", 5.0)];
+/// let mut sample = random::weighted_sample(items, 2, &mut random::seeded(5));
+/// sample.sort();
+/// assert_eq!(sample, ["a", "c"]);
+/// ```
+#[cfg(feature = "std")]
+pub fn weighted_sample<T, I, R>(items: I, k: usize, rng: &mut R) -> Vec<T>
+where
+    I: IntoIterator<Item = (T, f64)>,
+    R: Rng + ?Sized,
+{
+    if k == 0 {
+        return vec![];
+    }
+    // min-heap of the k largest keys u^(1/w) seen so far
+    let mut heap: BinaryHeap<Keyed<T>> = BinaryHeap::with_capacity(k + 1);
+    for (item, w) in items {
+        if !(w > 0.0 && w.is_finite()) {
+            continue;
+        }
+        let u: f64 = rng.gen();
+        let key = u.powf(1.0 / w);
+        if heap.len() < k {
+            heap.push(Keyed { key, item });
+        } else if heap.peek().map(|min| key > min.key) == Some(true) {
+            heap.pop();
+            heap.push(Keyed { key, item });
+        }
+    }
+    heap.into_iter().map(|k| k.item).collect()
+}
+
+#[cfg(feature = "std")]
+struct Keyed<T> {
+    key: f64,
+    item: T,
+}
+
+#[cfg(feature = "std")]
+impl<T> PartialEq for Keyed<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> Eq for Keyed<T> {}
+
+#[cfg(feature = "std")]
+impl<T> PartialOrd for Keyed<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// reversed, making `BinaryHeap` a min-heap
+#[cfg(feature = "std")]
+impl<T> Ord for Keyed<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.key.total_cmp(&self.key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verify;
+
+    #[test]
+    fn test() {
+        let keys: Vec<u32> = (0..50).collect();
+        let mut rng = seeded(42);
+
+        let mut shuffled = keys.clone();
+        shuffle(&mut shuffled, &mut rng);
+        assert!(verify::is_permutation_of(&keys, &shuffled));
+        assert_ne!(keys, shuffled);
+
+        // each key should lead a partial shuffle about equally often
+        let mut firsts = [0; 5];
+        for _ in 0..5000 {
+            let mut k = [0, 1, 2, 3, 4];
+            partial_shuffle(&mut k, 1, &mut rng);
+            firsts[k[0]] += 1;
+        }
+        assert!(firsts.iter().all(|&n| n > 850 && n < 1150), "{:?}", firsts);
+
+        let sample = reservoir_sample(keys.iter().cloned(), 10, &mut rng);
+        assert_eq!(sample.len(), 10);
+        assert!(sample.iter().all(|k| keys.contains(k)));
+        assert_eq!(reservoir_sample(0..3, 10, &mut rng), [0, 1, 2]);
+
+        let mut chosen = [0; 3];
+        for _ in 0..4000 {
+            chosen[weighted_choice(&[1.0, 3.0, f64::NAN], &mut rng).unwrap()] += 1;
+        }
+        assert_eq!(chosen[2], 0);
+        assert!(chosen[1] > 2 * chosen[0]);
+        assert_eq!(weighted_choice(&[0.0, -1.0], &mut rng), None);
+
+        #[cfg(feature = "std")]
+        {
+            let sample = weighted_sample(keys.iter().map(|&k| (k, (k % 2) as f64)), 10, &mut rng);
+            assert_eq!(sample.len(), 10);
+            assert!(sample.iter().all(|k| k % 2 == 1));
+            assert_eq!(weighted_sample(vec![(1, 1.0)], 0, &mut rng), []);
+        }
+    }
+}
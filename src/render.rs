@@ -0,0 +1,313 @@
+//! Drawings of the search trees, as Graphviz DOT or as SVG laid out
+//! here, for debugging their shapes.
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+
+use crate::tree::TreeNode;
+
+/// A node to draw, with its place in the drawing.
+pub(crate) struct Drawn {
+    pub key: String,
+    pub value: String,
+    pub n: usize,
+    /// Whether the link from the parent is red.
+    pub red: bool,
+    pub parent: Option<usize>,
+    pub is_left: bool,
+    pub depth: usize,
+    /// Position in key order, found by the in-order walk.
+    pub x: usize,
+}
+
+/// The nodes below `root` in pre-order, placed for drawing. `red` tells
+/// whether the link into a node is drawn as a red one.
+pub(crate) fn drawing<N, R>(root: Option<&N>, red: R) -> Vec<Drawn>
+where
+    N: TreeNode,
+    N::Key: fmt::Display,
+    N::Value: fmt::Display,
+    R: Fn(&N) -> bool,
+{
+    Walk::new(root, red).nodes
+}
+
+/// The nodes of a tree in pre-order, each placed by an in-order walk
+/// with an explicit stack. Drawings don't trust the subtree counts, since
+/// they're for looking at trees that may be broken.
+struct Walk<'a, N> {
+    nodes: Vec<Drawn>,
+    stack: Vec<(&'a N, usize)>,
+}
+
+impl<'a, N> Walk<'a, N>
+where
+    N: TreeNode,
+    N::Key: fmt::Display,
+    N::Value: fmt::Display,
+{
+    fn new<R: Fn(&N) -> bool>(root: Option<&'a N>, red: R) -> Self {
+        let mut walk = Walk {
+            nodes: vec![],
+            stack: vec![],
+        };
+        walk.push_left(root, None, false, &red);
+        let mut x = 0;
+        while let Some((node, i)) = walk.stack.pop() {
+            walk.nodes[i].x = x;
+            x += 1;
+            walk.push_left(node.right(), Some(i), false, &red);
+        }
+        walk
+    }
+
+    /// Push `node` and its chain of left children.
+    fn push_left<R: Fn(&N) -> bool>(
+        &mut self,
+        mut node: Option<&'a N>,
+        mut parent: Option<usize>,
+        mut is_left: bool,
+        red: &R,
+    ) {
+        while let Some(current) = node {
+            let i = self.nodes.len();
+            self.nodes.push(Drawn {
+                key: label(current.key()),
+                value: label(current.value()),
+                n: current.size(),
+                red: red(current),
+                parent,
+                is_left,
+                depth: parent.map_or(0, |p| self.nodes[p].depth + 1),
+                x: 0,
+            });
+            self.stack.push((current, i));
+            node = current.left();
+            parent = Some(i);
+            is_left = true;
+        }
+    }
+}
+
+/// Format `value` into a new string.
+fn label<T: fmt::Display + ?Sized>(value: &T) -> String {
+    let mut label = String::new();
+    let _ = write!(label, "{}", value);
+    label
+}
+
+/// The nodes as a Graphviz digraph, red links drawn red and thick. Each
+/// node shows its key, value and subtree count; an invisible node holds
+/// the place of a missing child so that the other one leans the right way.
+pub(crate) fn dot(nodes: &[Drawn]) -> String {
+    let mut out = String::new();
+    let _ = write_dot(nodes, &mut out);
+    out
+}
+
+fn write_dot(nodes: &[Drawn], out: &mut String) -> fmt::Result {
+    writeln!(out, "digraph {{")?;
+    writeln!(out, "    graph [ordering=out];")?;
+    writeln!(out, "    node [shape=box, style=rounded];")?;
+    for (i, node) in nodes.iter().enumerate() {
+        writeln!(
+            out,
+            "    n{} [label=\"{}\\n{}\\nn = {}\"];",
+            i,
+            escape_dot(&node.key),
+            escape_dot(&node.value),
+            node.n
+        )?;
+    }
+    let mut children = vec![(None, None); nodes.len()];
+    for (i, node) in nodes.iter().enumerate() {
+        if let Some(p) = node.parent {
+            if node.is_left {
+                children[p].0 = Some(i);
+            } else {
+                children[p].1 = Some(i);
+            }
+        }
+    }
+    for (i, &(left, right)) in children.iter().enumerate() {
+        if left.is_none() && right.is_none() {
+            continue;
+        }
+        for (side, child) in [("l", left), ("r", right)].iter() {
+            match *child {
+                Some(c) if nodes[c].red => {
+                    writeln!(out, "    n{} -> n{} [color=red, penwidth=2];", i, c)?
+                }
+                Some(c) => writeln!(out, "    n{} -> n{};", i, c)?,
+                None => {
+                    writeln!(out, "    n{}{} [label=\"\", style=invis];", i, side)?;
+                    writeln!(out, "    n{} -> n{}{} [style=invis];", i, i, side)?;
+                }
+            }
+        }
+    }
+    writeln!(out, "}}")
+}
+
+fn escape_dot(label: &str) -> String {
+    let mut escaped = String::with_capacity(label.len());
+    for c in label.chars() {
+        match c {
+            '"' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Horizontal distance between keys adjacent in order.
+const DX: usize = 40;
+/// Vertical distance between levels.
+const DY: usize = 60;
+const RADIUS: usize = 16;
+
+/// The nodes as an SVG picture: keys left to right in order, levels top
+/// to bottom. A node shows its key, with value and subtree count in a
+/// tooltip.
+pub(crate) fn svg(nodes: &[Drawn]) -> String {
+    let mut out = String::new();
+    let _ = write_svg(nodes, &mut out);
+    out
+}
+
+fn write_svg(nodes: &[Drawn], out: &mut String) -> fmt::Result {
+    let levels = nodes.iter().map(|node| node.depth + 1).max().unwrap_or(0);
+    let width = nodes.len().max(1) * DX;
+    let height = levels.max(1) * DY;
+    let center = |node: &Drawn| (node.x * DX + DX / 2, node.depth * DY + DY / 2);
+    writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+         viewBox=\"0 0 {} {}\" font-family=\"sans-serif\" font-size=\"11\">",
+        width, height, width, height
+    )?;
+    for node in nodes {
+        if let Some(p) = node.parent {
+            let ((x1, y1), (x2, y2)) = (center(&nodes[p]), center(node));
+            let (stroke, stroke_width) = if node.red { ("red", 3) } else { ("black", 1) };
+            writeln!(
+                out,
+                "  <line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" stroke-width=\"{}\"/>",
+                x1, y1, x2, y2, stroke, stroke_width
+            )?;
+        }
+    }
+    for node in nodes {
+        let (x, y) = center(node);
+        writeln!(out, "  <g>")?;
+        writeln!(
+            out,
+            "    <title>{}: {} (n = {})</title>",
+            escape_xml(&node.key),
+            escape_xml(&node.value),
+            node.n
+        )?;
+        writeln!(
+            out,
+            "    <circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"white\" stroke=\"black\"/>",
+            x, y, RADIUS
+        )?;
+        writeln!(
+            out,
+            "    <text x=\"{}\" y=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>",
+            x,
+            y,
+            escape_xml(&node.key)
+        )?;
+        writeln!(out, "  </g>")?;
+    }
+    writeln!(out, "</svg>")
+}
+
+fn escape_xml(label: &str) -> String {
+    let mut escaped = String::with_capacity(label.len());
+    for c in label.chars() {
+        match c {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::boxed::Box;
+
+    /// A node of a test tree: key and children.
+    struct Node(u32, Option<Box<Node>>, Option<Box<Node>>);
+
+    impl TreeNode for Node {
+        type Key = u32;
+        type Value = &'static str;
+
+        fn key(&self) -> &u32 {
+            &self.0
+        }
+        fn value(&self) -> &&'static str {
+            &"<v>"
+        }
+        fn left(&self) -> Option<&Node> {
+            self.1.as_deref()
+        }
+        fn right(&self) -> Option<&Node> {
+            self.2.as_deref()
+        }
+        fn size(&self) -> usize {
+            1
+        }
+    }
+
+    #[test]
+    fn test() {
+        // 2 with children 1 and 4, 4 with left child 3
+        let leaf = |k| Some(Box::new(Node(k, None, None)));
+        let tree = Node(2, leaf(1), Some(Box::new(Node(4, leaf(3), None))));
+        let walk = Walk::new(Some(&tree), |node| node.0 == 3);
+        let placed: Vec<_> = walk
+            .nodes
+            .iter()
+            .map(|node| (node.key.as_str(), node.x, node.depth, node.parent))
+            .collect();
+        assert_eq!(
+            placed,
+            [
+                ("2", 1, 0, None),
+                ("1", 0, 1, Some(0)),
+                ("4", 3, 1, Some(0)),
+                ("3", 2, 2, Some(2)),
+            ]
+        );
+
+        let dot = dot(&walk.nodes);
+        assert!(dot.starts_with("digraph {\n"));
+        assert!(dot.contains("n0 [label=\"2\\n<v>\\nn = 1\"];"));
+        assert!(dot.contains("n0 -> n1;\n    n0 -> n2;"));
+        assert!(
+            dot.contains("n2 -> n3 [color=red, penwidth=2];\n    n2r [label=\"\", style=invis];")
+        );
+        assert_eq!(escape_dot("say \"hi\""), "say \\\"hi\\\"");
+
+        let svg = svg(&walk.nodes);
+        assert_eq!(svg.matches("<circle").count(), 4);
+        assert_eq!(svg.matches("<line").count(), 3);
+        assert_eq!(svg.matches("stroke=\"red\"").count(), 1);
+        assert!(svg.contains("<title>3: &lt;v&gt; (n = 1)</title>"));
+        assert!(svg.contains("width=\"160\" height=\"180\""));
+        assert!(svg.trim_end().ends_with("</svg>"));
+    }
+}
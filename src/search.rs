@@ -0,0 +1,162 @@
+//! Binary search over sorted slices.
+//!
+//! The semantics follow the ordered symbol table operations of
+//! `BinarySearchTree`, so that a slice sorted with `Sorting` can serve as
+//! a static symbol table: `rank` is `rank_of_key`, `floor` is `floor` and
+//! `keys[rank]` is `key_of_rank(rank)`.
+//!
+//! The `*_by` variants take a function telling whether a key of the slice
+//! is `Less`, `Equal` or `Greater` than the one searched for, like
+//! `slice::binary_search_by`.
+use core::cmp::Ordering;
+use core::ops::Range;
+
+/// Number of keys smaller than `key`.
+/// ```
+/// # use algorithm_exercise::search;
+/// let keys = [1, 3, 3, 5];
+/// assert_eq!(search::rank(&keys, &3), 1);
+/// assert_eq!(search::rank(&keys, &4), 3);
+/// ```
+pub fn rank<T: PartialOrd>(keys: &[T], key: &T) -> usize {
+    lower_bound(keys, key)
+}
+
+/// Index of the first key not smaller than `key`, or `keys.len()` if
+/// there is none.
+pub fn lower_bound<T: PartialOrd>(keys: &[T], key: &T) -> usize {
+    keys.partition_point(|k| k < key)
+}
+
+/// Index of the first key greater than `key`, or `keys.len()` if there
+/// is none.
+pub fn upper_bound<T: PartialOrd>(keys: &[T], key: &T) -> usize {
+    keys.partition_point(|k| k <= key)
+}
+
+/// Indices of the keys equal to `key`.
+/// ```
+/// # use algorithm_exercise::search;
+/// let keys = [1, 3, 3, 5];
+/// assert_eq!(search::equal_range(&keys, &3), 1..3);
+/// assert_eq!(search::equal_range(&keys, &4), 3..3);
+/// ```
+pub fn equal_range<T: PartialOrd>(keys: &[T], key: &T) -> Range<usize> {
+    lower_bound(keys, key)..upper_bound(keys, key)
+}
+
+/// The largest key less than or equal to `key`.
+/// ```
+/// # use algorithm_exercise::search;
+/// let keys = [1, 3, 5];
+/// assert_eq!(search::floor(&keys, &4), Some(&3));
+/// assert_eq!(search::floor(&keys, &0), None);
+/// ```
+pub fn floor<'a, T: PartialOrd>(keys: &'a [T], key: &T) -> Option<&'a T> {
+    before(keys, upper_bound(keys, key))
+}
+
+/// The smallest key greater than or equal to `key`.
+/// ```
+/// # use algorithm_exercise::search;
+/// let keys = [1, 3, 5];
+/// assert_eq!(search::ceiling(&keys, &4), Some(&5));
+/// assert_eq!(search::ceiling(&keys, &6), None);
+/// ```
+pub fn ceiling<'a, T: PartialOrd>(keys: &'a [T], key: &T) -> Option<&'a T> {
+    keys.get(lower_bound(keys, key))
+}
+
+/// `rank` with the comparison done by `f`.
+pub fn rank_by<T, F>(keys: &[T], f: F) -> usize
+where
+    F: FnMut(&T) -> Ordering,
+{
+    lower_bound_by(keys, f)
+}
+
+/// `lower_bound` with the comparison done by `f`.
+pub fn lower_bound_by<T, F>(keys: &[T], mut f: F) -> usize
+where
+    F: FnMut(&T) -> Ordering,
+{
+    keys.partition_point(|k| f(k) == Ordering::Less)
+}
+
+/// `upper_bound` with the comparison done by `f`.
+pub fn upper_bound_by<T, F>(keys: &[T], mut f: F) -> usize
+where
+    F: FnMut(&T) -> Ordering,
+{
+    keys.partition_point(|k| f(k) != Ordering::Greater)
+}
+
+/// `equal_range` with the comparison done by `f`.
+/// ```
+/// # use algorithm_exercise::search;
+/// let people = [("ann", 31), ("bob", 25), ("cid", 25), ("dan", 40)];
+/// let by_name = |p: &(&str, u32)| p.0.cmp("cid");
+/// assert_eq!(search::equal_range_by(&people, by_name), 2..3);
+/// ```
+pub fn equal_range_by<T, F>(keys: &[T], mut f: F) -> Range<usize>
+where
+    F: FnMut(&T) -> Ordering,
+{
+    lower_bound_by(keys, &mut f)..upper_bound_by(keys, &mut f)
+}
+
+/// `floor` with the comparison done by `f`.
+pub fn floor_by<T, F>(keys: &[T], f: F) -> Option<&T>
+where
+    F: FnMut(&T) -> Ordering,
+{
+    before(keys, upper_bound_by(keys, f))
+}
+
+/// `ceiling` with the comparison done by `f`.
+pub fn ceiling_by<T, F>(keys: &[T], f: F) -> Option<&T>
+where
+    F: FnMut(&T) -> Ordering,
+{
+    keys.get(lower_bound_by(keys, f))
+}
+
+fn before<T>(keys: &[T], i: usize) -> Option<&T> {
+    i.checked_sub(1).map(|i| &keys[i])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BinarySearchTree, Sorting};
+    use alloc::vec;
+
+    #[test]
+    fn test() {
+        let mut keys = vec![7, 1, 9, 3, 5, 11];
+        Sorting::shellsort(&mut keys);
+        let mut table = BinarySearchTree::new();
+        for &k in &keys {
+            table.put(k, ());
+        }
+        for k in 0..13 {
+            assert_eq!(rank(&keys, &k), table.rank_of_key(&k));
+            assert_eq!(floor(&keys, &k), table.floor(&k));
+            assert_eq!(ceiling(&keys, &k), table.ceiling(&k));
+            assert_eq!(rank_by(&keys, |p| p.cmp(&k)), rank(&keys, &k));
+            assert_eq!(floor_by(&keys, |p| p.cmp(&k)), floor(&keys, &k));
+            assert_eq!(ceiling_by(&keys, |p| p.cmp(&k)), ceiling(&keys, &k));
+        }
+        assert_eq!(keys[rank(&keys, &9)], 9);
+
+        let keys = [1, 2, 2, 2, 3];
+        assert_eq!(equal_range(&keys, &2), 1..4);
+        assert_eq!(equal_range_by(&keys, |p| p.cmp(&2)), 1..4);
+        assert_eq!(upper_bound_by(&keys, |p| p.cmp(&0)), 0);
+        assert_eq!(upper_bound(&keys, &3), 5);
+        assert_eq!(lower_bound(&keys, &3), 4);
+        assert_eq!(ceiling(&keys, &2), Some(&2));
+        assert_eq!(floor(&keys, &2), Some(&2));
+        assert_eq!(ceiling::<i32>(&[], &2), None);
+    }
+}
@@ -0,0 +1,365 @@
+//! Method-call syntax for the algorithms of `Sorting`.
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+#[cfg(feature = "std")]
+use rand::thread_rng;
+use rand::Rng;
+
+use crate::sorting;
+
+/// The algorithms of `Sorting` as methods on slices and vectors, plus
+/// comparator based `*_by` variants.
+/// ```
+/// # use algorithm_exercise::*;
+/// let mut keys = vec![3, 1, 2];
+/// keys.quicksort_with(&mut random::seeded(0));
+/// assert_eq!(keys, [1, 2, 3]);
+/// keys.shellsort_by(|a, b| b.cmp(a));
+/// assert_eq!(keys, [3, 2, 1]);
+/// ```
+pub trait SortExt<T> {
+    /// See `Sorting::insertion_sort`.
+    fn insertion_sort(&mut self)
+    where
+        T: PartialOrd + Clone;
+
+    /// See `Sorting::selection_sort`.
+    fn selection_sort(&mut self)
+    where
+        T: PartialOrd;
+
+    /// See `Sorting::bubble_sort`.
+    fn bubble_sort(&mut self)
+    where
+        T: PartialOrd;
+
+    /// See `Sorting::shellsort`.
+    fn shellsort(&mut self)
+    where
+        T: PartialOrd + Clone;
+
+    /// See `Sorting::quicksort`.
+    #[cfg(feature = "std")]
+    fn quicksort(&mut self)
+    where
+        T: PartialOrd + Clone;
+
+    /// See `Sorting::quicksort_with`.
+    fn quicksort_with<R: Rng + ?Sized>(&mut self, rng: &mut R)
+    where
+        T: PartialOrd + Clone;
+
+    /// Insertion sort ordering keys by `compare`.
+    fn insertion_sort_by<F>(&mut self, compare: F)
+    where
+        T: Clone,
+        F: FnMut(&T, &T) -> Ordering;
+
+    /// Selection sort ordering keys by `compare`.
+    fn selection_sort_by<F>(&mut self, compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering;
+
+    /// Bubble sort ordering keys by `compare`.
+    fn bubble_sort_by<F>(&mut self, compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering;
+
+    /// Shellsort ordering keys by `compare`.
+    fn shellsort_by<F>(&mut self, compare: F)
+    where
+        T: Clone,
+        F: FnMut(&T, &T) -> Ordering;
+
+    /// Quicksort ordering keys by `compare`.
+    #[cfg(feature = "std")]
+    fn quicksort_by<F>(&mut self, compare: F)
+    where
+        T: Clone,
+        F: FnMut(&T, &T) -> Ordering;
+
+    /// Quicksort ordering keys by `compare`, shuffling them with `rng`.
+    /// Needs no `std`.
+    /// ```
+    /// # use algorithm_exercise::*;
+    /// let mut keys = [1, 3, 2];
+    /// keys.quicksort_by_with(|a, b| b.cmp(a), &mut random::seeded(0));
+    /// assert_eq!(keys, [3, 2, 1]);
+    /// ```
+    fn quicksort_by_with<F, R>(&mut self, compare: F, rng: &mut R)
+    where
+        T: Clone,
+        F: FnMut(&T, &T) -> Ordering,
+        R: Rng + ?Sized;
+}
+
+fn less<T, F: FnMut(&T, &T) -> Ordering>(mut compare: F) -> impl FnMut(&T, &T) -> bool {
+    move |a, b| compare(a, b) == Ordering::Less
+}
+
+impl<T> SortExt<T> for [T] {
+    fn insertion_sort(&mut self)
+    where
+        T: PartialOrd + Clone,
+    {
+        sorting::insertion_sort_by(self, PartialOrd::lt)
+    }
+
+    fn selection_sort(&mut self)
+    where
+        T: PartialOrd,
+    {
+        sorting::selection_sort_by(self, PartialOrd::lt)
+    }
+
+    fn bubble_sort(&mut self)
+    where
+        T: PartialOrd,
+    {
+        sorting::bubble_sort_by(self, PartialOrd::lt)
+    }
+
+    fn shellsort(&mut self)
+    where
+        T: PartialOrd + Clone,
+    {
+        sorting::shellsort_by(self, PartialOrd::lt)
+    }
+
+    #[cfg(feature = "std")]
+    fn quicksort(&mut self)
+    where
+        T: PartialOrd + Clone,
+    {
+        sorting::quicksort_by(self, PartialOrd::lt, &mut thread_rng())
+    }
+
+    fn quicksort_with<R: Rng + ?Sized>(&mut self, rng: &mut R)
+    where
+        T: PartialOrd + Clone,
+    {
+        sorting::quicksort_by(self, PartialOrd::lt, rng)
+    }
+
+    fn insertion_sort_by<F>(&mut self, compare: F)
+    where
+        T: Clone,
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        sorting::insertion_sort_by(self, less(compare))
+    }
+
+    fn selection_sort_by<F>(&mut self, compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        sorting::selection_sort_by(self, less(compare))
+    }
+
+    fn bubble_sort_by<F>(&mut self, compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        sorting::bubble_sort_by(self, less(compare))
+    }
+
+    fn shellsort_by<F>(&mut self, compare: F)
+    where
+        T: Clone,
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        sorting::shellsort_by(self, less(compare))
+    }
+
+    #[cfg(feature = "std")]
+    fn quicksort_by<F>(&mut self, compare: F)
+    where
+        T: Clone,
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        sorting::quicksort_by(self, less(compare), &mut thread_rng())
+    }
+
+    fn quicksort_by_with<F, R>(&mut self, compare: F, rng: &mut R)
+    where
+        T: Clone,
+        F: FnMut(&T, &T) -> Ordering,
+        R: Rng + ?Sized,
+    {
+        sorting::quicksort_by(self, less(compare), rng)
+    }
+}
+
+impl<T> SortExt<T> for Vec<T> {
+    fn insertion_sort(&mut self)
+    where
+        T: PartialOrd + Clone,
+    {
+        self.as_mut_slice().insertion_sort()
+    }
+
+    fn selection_sort(&mut self)
+    where
+        T: PartialOrd,
+    {
+        self.as_mut_slice().selection_sort()
+    }
+
+    fn bubble_sort(&mut self)
+    where
+        T: PartialOrd,
+    {
+        self.as_mut_slice().bubble_sort()
+    }
+
+    fn shellsort(&mut self)
+    where
+        T: PartialOrd + Clone,
+    {
+        self.as_mut_slice().shellsort()
+    }
+
+    #[cfg(feature = "std")]
+    fn quicksort(&mut self)
+    where
+        T: PartialOrd + Clone,
+    {
+        self.as_mut_slice().quicksort()
+    }
+
+    fn quicksort_with<R: Rng + ?Sized>(&mut self, rng: &mut R)
+    where
+        T: PartialOrd + Clone,
+    {
+        self.as_mut_slice().quicksort_with(rng)
+    }
+
+    fn insertion_sort_by<F>(&mut self, compare: F)
+    where
+        T: Clone,
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        self.as_mut_slice().insertion_sort_by(compare)
+    }
+
+    fn selection_sort_by<F>(&mut self, compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        self.as_mut_slice().selection_sort_by(compare)
+    }
+
+    fn bubble_sort_by<F>(&mut self, compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        self.as_mut_slice().bubble_sort_by(compare)
+    }
+
+    fn shellsort_by<F>(&mut self, compare: F)
+    where
+        T: Clone,
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        self.as_mut_slice().shellsort_by(compare)
+    }
+
+    #[cfg(feature = "std")]
+    fn quicksort_by<F>(&mut self, compare: F)
+    where
+        T: Clone,
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        self.as_mut_slice().quicksort_by(compare)
+    }
+
+    fn quicksort_by_with<F, R>(&mut self, compare: F, rng: &mut R)
+    where
+        T: Clone,
+        F: FnMut(&T, &T) -> Ordering,
+        R: Rng + ?Sized,
+    {
+        self.as_mut_slice().quicksort_by_with(compare, rng)
+    }
+}
+
+/// Collect an iterator and sort it with a chosen algorithm.
+pub trait SortedIterator: Iterator + Sized {
+    /// Collect the items and sort them with `alg`.
+    /// ```
+    /// # use algorithm_exercise::*;
+    /// let words = vec!["pear", "fig", "apple"];
+    /// let sorted: Vec<_> = words.into_iter().sorted(Sorting::shellsort).collect();
+    /// assert_eq!(sorted, ["apple", "fig", "pear"]);
+    /// let by_len: Vec<_> = sorted
+    ///     .into_iter()
+    ///     .sorted(|keys: &mut [&str]| keys.insertion_sort_by(|a, b| a.len().cmp(&b.len())))
+    ///     .collect();
+    /// assert_eq!(by_len, ["fig", "pear", "apple"]);
+    /// ```
+    fn sorted<F>(self, alg: F) -> vec::IntoIter<Self::Item>
+    where
+        F: FnOnce(&mut [Self::Item]),
+    {
+        let mut keys: Vec<Self::Item> = self.collect();
+        alg(&mut keys);
+        keys.into_iter()
+    }
+}
+
+impl<I: Iterator> SortedIterator for I {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{random, verify};
+
+    #[test]
+    fn test() {
+        let keys_original = vec![5, 4, 3, 6, 7, 8, 9, 0, 1, 2, 5, 4, 3, 6, 8, 0, 7, 5];
+        let mut sorted = keys_original.clone();
+        sorted.sort_unstable();
+        let mut reversed = sorted.clone();
+        reversed.reverse();
+
+        let sortings: Vec<fn(&mut Vec<i32>)> = vec![
+            |keys| keys.insertion_sort(),
+            |keys| keys.selection_sort(),
+            |keys| keys.bubble_sort(),
+            |keys| keys.shellsort(),
+            |keys| keys.quicksort_with(&mut random::seeded(1)),
+        ];
+        #[cfg(feature = "std")]
+        let sortings = [sortings, vec![|keys| keys.quicksort()]].concat();
+        for sort in sortings {
+            let mut keys = keys_original.clone();
+            sort(&mut keys);
+            assert_eq!(sorted, keys);
+        }
+
+        let sortings: Vec<fn(&mut [i32])> = vec![
+            |keys| keys.insertion_sort_by(|a, b| b.cmp(a)),
+            |keys| keys.selection_sort_by(|a, b| b.cmp(a)),
+            |keys| keys.bubble_sort_by(|a, b| b.cmp(a)),
+            |keys| keys.shellsort_by(|a, b| b.cmp(a)),
+            |keys| keys.quicksort_by_with(|a, b| b.cmp(a), &mut random::seeded(1)),
+        ];
+        #[cfg(feature = "std")]
+        let sortings = [sortings, vec![|keys| keys.quicksort_by(|a, b| b.cmp(a))]].concat();
+        for sort in sortings {
+            let mut keys = keys_original.clone();
+            sort(&mut keys[..]);
+            assert_eq!(reversed, keys);
+        }
+
+        let by_parity = |keys: &mut [i32]| keys.insertion_sort_by(|a, b| (a % 2).cmp(&(b % 2)));
+        assert!(verify::is_stable_sort(
+            |keys| keys.insertion_sort_by(|a, b| a.value.cmp(&b.value)),
+            &keys_original
+        ));
+        let evens_first: Vec<_> = keys_original.iter().cloned().sorted(by_parity).collect();
+        assert_eq!(evens_first[..3], [4, 6, 8]);
+    }
+}
@@ -0,0 +1,271 @@
+//! Compound lexicographic orderings built from key extractors.
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+/// Where `None` goes when ordering by an `Option` field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Nulls {
+    First,
+    Last,
+}
+
+/// A lexicographic ordering over several keys of a record.
+///
+/// Records are ordered by the first key, ties broken by the next, and so
+/// on. Keys that can't be compared count as a tie.
+/// ```
+/// # use algorithm_exercise::*;
+/// #[derive(Clone)]
+/// struct Employee { dept: u32, salary: u32, name: &'static str }
+/// let mut staff = vec![
+///     Employee { dept: 2, salary: 50, name: "eve" },
+///     Employee { dept: 1, salary: 40, name: "bob" },
+///     Employee { dept: 1, salary: 60, name: "amy" },
+///     Employee { dept: 1, salary: 40, name: "ann" },
+/// ];
+/// let spec = SortSpec::new()
+///     .by(|e: &Employee| e.dept)
+///     .then_desc(|e| e.salary)
+///     .then(|e| e.name);
+/// staff.insertion_sort_by(|a, b| spec.compare(a, b));
+/// let names: Vec<_> = staff.iter().map(|e| e.name).collect();
+/// assert_eq!(names, ["amy", "ann", "bob", "eve"]);
+/// ```
+pub struct SortSpec<'a, T> {
+    keys: Vec<Compare<'a, T>>,
+}
+
+type Compare<'a, T> = Box<dyn Fn(&T, &T) -> Ordering + 'a>;
+
+impl<'a, T> SortSpec<'a, T> {
+    /// Initializer. With no keys all records are equal.
+    pub fn new() -> Self {
+        SortSpec { keys: vec![] }
+    }
+
+    /// Order by `key`, ascending. Same as `then`, reads better first.
+    pub fn by<K, F>(self, key: F) -> Self
+    where
+        K: PartialOrd,
+        F: Fn(&T) -> K + 'a,
+    {
+        self.then(key)
+    }
+
+    /// Order by `key`, descending. Same as `then_desc`, reads better first.
+    pub fn by_desc<K, F>(self, key: F) -> Self
+    where
+        K: PartialOrd,
+        F: Fn(&T) -> K + 'a,
+    {
+        self.then_desc(key)
+    }
+
+    /// Break ties by `key`, ascending.
+    pub fn then<K, F>(self, key: F) -> Self
+    where
+        K: PartialOrd,
+        F: Fn(&T) -> K + 'a,
+    {
+        self.push(move |a, b| cmp(&key(a), &key(b)))
+    }
+
+    /// Break ties by `key`, descending.
+    pub fn then_desc<K, F>(self, key: F) -> Self
+    where
+        K: PartialOrd,
+        F: Fn(&T) -> K + 'a,
+    {
+        self.push(move |a, b| cmp(&key(b), &key(a)))
+    }
+
+    /// Break ties by an optional `key`, ascending, with `None` placed as
+    /// `nulls` says.
+    /// ```
+    /// # use algorithm_exercise::*;
+    /// let mut bonuses = vec![Some(3), None, Some(1)];
+    /// let spec = SortSpec::new().then_nullable(|b: &Option<i32>| *b, Nulls::Last);
+    /// bonuses.shellsort_by(|a, b| spec.compare(a, b));
+    /// assert_eq!(bonuses, [Some(1), Some(3), None]);
+    /// ```
+    pub fn then_nullable<K, F>(self, key: F, nulls: Nulls) -> Self
+    where
+        K: PartialOrd,
+        F: Fn(&T) -> Option<K> + 'a,
+    {
+        self.push(move |a, b| cmp_nullable(&key(a), &key(b), nulls, false))
+    }
+
+    /// Break ties by an optional `key`, descending, with `None` placed as
+    /// `nulls` says.
+    pub fn then_nullable_desc<K, F>(self, key: F, nulls: Nulls) -> Self
+    where
+        K: PartialOrd,
+        F: Fn(&T) -> Option<K> + 'a,
+    {
+        self.push(move |a, b| cmp_nullable(&key(a), &key(b), nulls, true))
+    }
+
+    fn push<F: Fn(&T, &T) -> Ordering + 'a>(mut self, compare: F) -> Self {
+        self.keys.push(Box::new(compare));
+        self
+    }
+
+    /// Compare two records, for use with comparator based sorts such as
+    /// the `SortExt::*_by` methods or `slice::sort_by`.
+    pub fn compare(&self, a: &T, b: &T) -> Ordering {
+        for key in &self.keys {
+            match key(a, b) {
+                Ordering::Equal => continue,
+                ordering => return ordering,
+            }
+        }
+        Ordering::Equal
+    }
+
+    /// Sort `records` with any algorithm of `Sorting`.
+    /// ```
+    /// # use algorithm_exercise::*;
+    /// let mut words = vec!["pear", "fig", "kiwi", "apple"];
+    /// let spec = SortSpec::new().by(|w: &&str| w.len()).then(|w| *w);
+    /// spec.sort(&mut words, Sorting::shellsort);
+    /// assert_eq!(words, ["fig", "kiwi", "pear", "apple"]);
+    /// ```
+    pub fn sort<'s, A>(&'s self, records: &mut [T], alg: A)
+    where
+        T: Clone,
+        A: FnOnce(&mut [BySpec<'s, T>]),
+    {
+        let spec: &'s SortSpec<'s, T> = self;
+        let mut wrapped: Vec<BySpec<'s, T>> = records
+            .iter()
+            .map(|record| BySpec {
+                record: record.clone(),
+                spec,
+            })
+            .collect();
+        alg(&mut wrapped);
+        for (record, w) in records.iter_mut().zip(wrapped) {
+            *record = w.record;
+        }
+    }
+}
+
+impl<'a, T> Default for SortSpec<'a, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A record ordered by a `SortSpec`, as handed to the algorithms of
+/// `Sorting` by `SortSpec::sort`.
+pub struct BySpec<'s, T> {
+    record: T,
+    spec: &'s SortSpec<'s, T>,
+}
+
+impl<'s, T: Clone> Clone for BySpec<'s, T> {
+    fn clone(&self) -> Self {
+        BySpec {
+            record: self.record.clone(),
+            spec: self.spec,
+        }
+    }
+}
+
+impl<'s, T> PartialEq for BySpec<'s, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.spec.compare(&self.record, &other.record) == Ordering::Equal
+    }
+}
+
+impl<'s, T> PartialOrd for BySpec<'s, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.spec.compare(&self.record, &other.record))
+    }
+}
+
+fn cmp<K: PartialOrd>(a: &K, b: &K) -> Ordering {
+    a.partial_cmp(b).unwrap_or(Ordering::Equal)
+}
+
+fn cmp_nullable<K: PartialOrd>(a: &Option<K>, b: &Option<K>, nulls: Nulls, desc: bool) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) if desc => cmp(b, a),
+        (Some(a), Some(b)) => cmp(a, b),
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) if nulls == Nulls::First => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) if nulls == Nulls::First => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{random, SortExt, Sorting};
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Row {
+        dept: &'static str,
+        salary: u32,
+        bonus: Option<f64>,
+        name: &'static str,
+    }
+
+    #[test]
+    fn test() {
+        let row = |dept, salary, bonus, name| Row {
+            dept,
+            salary,
+            bonus,
+            name,
+        };
+        let rows = vec![
+            row("ops", 50, None, "eve"),
+            row("dev", 70, Some(5.0), "bob"),
+            row("dev", 70, None, "amy"),
+            row("ops", 50, Some(1.0), "cat"),
+            row("dev", 90, Some(2.0), "dan"),
+            row("dev", 70, Some(9.0), "fay"),
+        ];
+        let names = |rows: &[Row]| rows.iter().map(|r| r.name).collect::<Vec<_>>();
+
+        let spec = SortSpec::new()
+            .by(|r: &Row| r.dept)
+            .then_desc(|r| r.salary)
+            .then_nullable_desc(|r| r.bonus, Nulls::Last)
+            .then(|r| r.name);
+        let expected = ["dan", "fay", "bob", "amy", "cat", "eve"];
+        let sortings = [
+            Sorting::insertion_sort as fn(&mut [_]),
+            Sorting::selection_sort,
+            Sorting::shellsort,
+            |records| Sorting::quicksort_with(records, &mut random::seeded(2)),
+            Sorting::bubble_sort,
+        ];
+        for sort in sortings {
+            let mut sorted = rows.clone();
+            spec.sort(&mut sorted, sort);
+            assert_eq!(names(&sorted), expected);
+        }
+        let mut sorted = rows.clone();
+        sorted.sort_by(|a, b| spec.compare(a, b));
+        assert_eq!(names(&sorted), expected);
+
+        // stable sorts keep the input order of ties
+        let spec = SortSpec::new()
+            .by(|r: &Row| r.salary)
+            .then_nullable(|r| r.bonus, Nulls::First);
+        let mut sorted = rows.clone();
+        sorted.insertion_sort_by(|a, b| spec.compare(a, b));
+        assert_eq!(names(&sorted), ["eve", "cat", "amy", "bob", "fay", "dan"]);
+        let spec = SortSpec::new().by_desc(|r: &Row| r.salary);
+        let mut sorted = rows;
+        sorted.bubble_sort_by(|a, b| spec.compare(a, b));
+        assert_eq!(names(&sorted), ["dan", "bob", "amy", "fay", "eve", "cat"]);
+    }
+}
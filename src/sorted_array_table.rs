@@ -0,0 +1,318 @@
+//! The ordered symbol table kept as parallel sorted arrays.
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::fmt;
+use core::iter::Zip;
+use core::mem;
+use core::ops::Range;
+use core::slice;
+
+use crate::search;
+use crate::symbol_table::OrderedSymbolTable;
+
+/// Ordered symbol table kept as two parallel sorted arrays, searched by
+/// binary search. Lookups and ordered operations take logarithmic time,
+/// but `put` and `delete` shift the keys after the one they touch.
+/// ```
+/// # use algorithm_exercise::*;
+/// let mut table = SortedArrayTable::new();
+/// table.put("b", 2);
+/// table.put("a", 1);
+/// table.put("c", 3);
+/// assert_eq!(table.get("b"), Some(&2));
+/// assert_eq!(table.rank_of_key("c"), 2);
+/// assert_eq!(table.floor("bb"), Some(&"b"));
+/// ```
+pub struct SortedArrayTable<K, V> {
+    keys: Vec<K>,
+    values: Vec<V>,
+}
+
+impl<K: Ord, V> SortedArrayTable<K, V> {
+    /// An empty table.
+    pub fn new() -> Self {
+        SortedArrayTable {
+            keys: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+
+    /// An empty table with room for `capacity` keys.
+    pub fn with_capacity(capacity: usize) -> Self {
+        SortedArrayTable {
+            keys: Vec::with_capacity(capacity),
+            values: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Number of keys.
+    pub fn size(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Whether the table has no keys.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Whether there is a value under `key`.
+    pub fn contains<Q: Ord + ?Sized>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.find(key).is_some()
+    }
+
+    /// The value under `key`.
+    pub fn get<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+    {
+        self.find(key).map(|i| &self.values[i])
+    }
+
+    /// Mutable reference to the value under `key`.
+    pub fn get_mut<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+    {
+        self.find(key).map(move |i| &mut self.values[i])
+    }
+
+    /// Insert `value` under `key`, returning the value it replaces.
+    pub fn put(&mut self, key: K, value: V) -> Option<V> {
+        let i = self.rank_of_key(&key);
+        if i < self.keys.len() && self.keys[i] == key {
+            return Some(mem::replace(&mut self.values[i], value));
+        }
+        self.keys.insert(i, key);
+        self.values.insert(i, value);
+        None
+    }
+
+    /// The smallest key.
+    pub fn min(&self) -> Option<&K> {
+        self.keys.first()
+    }
+
+    /// The largest key.
+    pub fn max(&self) -> Option<&K> {
+        self.keys.last()
+    }
+
+    /// The largest key less than or equal to `key`.
+    pub fn floor<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&K>
+    where
+        K: Borrow<Q>,
+    {
+        search::floor_by(&self.keys, |k| k.borrow().cmp(key))
+    }
+
+    /// The smallest key greater than or equal to `key`.
+    pub fn ceiling<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&K>
+    where
+        K: Borrow<Q>,
+    {
+        search::ceiling_by(&self.keys, |k| k.borrow().cmp(key))
+    }
+
+    /// The key with `rank` smaller keys.
+    pub fn key_of_rank(&self, rank: usize) -> Option<&K> {
+        self.keys.get(rank)
+    }
+
+    /// Number of keys less than `key`.
+    pub fn rank_of_key<Q: Ord + ?Sized>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+    {
+        search::rank_by(&self.keys, |k| k.borrow().cmp(key))
+    }
+
+    /// Remove the smallest key.
+    pub fn pop_min(&mut self) -> Option<(K, V)> {
+        if self.is_empty() {
+            return None;
+        }
+        Some(self.remove_at(0))
+    }
+
+    /// Remove the largest key.
+    pub fn pop_max(&mut self) -> Option<(K, V)> {
+        Some((self.keys.pop()?, self.values.pop()?))
+    }
+
+    /// Remove `key` and its value, if present.
+    pub fn delete<Q: Ord + ?Sized>(&mut self, key: &Q)
+    where
+        K: Borrow<Q>,
+    {
+        self.remove(key);
+    }
+
+    /// Remove `key`, returning it with its value.
+    pub fn remove<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+    {
+        self.find(key).map(|i| self.remove_at(i))
+    }
+
+    /// Number of keys from `lo` to `hi`, both included.
+    pub fn size_range<Q: Ord + ?Sized>(&self, lo: &Q, hi: &Q) -> usize
+    where
+        K: Borrow<Q>,
+    {
+        self.range_of(lo, hi).len()
+    }
+
+    /// Key-value pairs in key order.
+    pub fn iter(&self) -> Zip<slice::Iter<'_, K>, slice::Iter<'_, V>> {
+        self.keys.iter().zip(self.values.iter())
+    }
+
+    /// Keys in order.
+    pub fn keys(&self) -> slice::Iter<'_, K> {
+        self.keys.iter()
+    }
+
+    /// Values in key order.
+    pub fn values(&self) -> slice::Iter<'_, V> {
+        self.values.iter()
+    }
+
+    fn find<Q: Ord + ?Sized>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+    {
+        self.keys.binary_search_by(|k| k.borrow().cmp(key)).ok()
+    }
+
+    fn range_of<Q: Ord + ?Sized>(&self, lo: &Q, hi: &Q) -> Range<usize>
+    where
+        K: Borrow<Q>,
+    {
+        let start = search::lower_bound_by(&self.keys, |k| k.borrow().cmp(lo));
+        let end = search::upper_bound_by(&self.keys, |k| k.borrow().cmp(hi));
+        start..end.max(start)
+    }
+
+    fn remove_at(&mut self, i: usize) -> (K, V) {
+        (self.keys.remove(i), self.values.remove(i))
+    }
+}
+
+impl<K: Ord, V> Default for SortedArrayTable<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord + fmt::Debug, V: fmt::Debug> fmt::Debug for SortedArrayTable<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: Ord, V> OrderedSymbolTable<K, V> for SortedArrayTable<K, V> {
+    fn put(&mut self, key: K, value: V) -> Option<V> {
+        self.put(key, value)
+    }
+
+    fn get<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+    {
+        self.get(key)
+    }
+
+    fn delete<Q: Ord + ?Sized>(&mut self, key: &Q)
+    where
+        K: Borrow<Q>,
+    {
+        self.delete(key)
+    }
+
+    fn size(&self) -> usize {
+        self.size()
+    }
+
+    fn min(&self) -> Option<&K> {
+        self.min()
+    }
+
+    fn max(&self) -> Option<&K> {
+        self.max()
+    }
+
+    fn floor<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&K>
+    where
+        K: Borrow<Q>,
+    {
+        self.floor(key)
+    }
+
+    fn ceiling<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&K>
+    where
+        K: Borrow<Q>,
+    {
+        self.ceiling(key)
+    }
+
+    fn rank<Q: Ord + ?Sized>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+    {
+        self.rank_of_key(key)
+    }
+
+    fn select(&self, rank: usize) -> Option<&K> {
+        self.key_of_rank(rank)
+    }
+
+    fn keys<'a, Q: Ord + ?Sized>(&'a self, lo: &Q, hi: &Q) -> Box<dyn Iterator<Item = &'a K> + 'a>
+    where
+        K: Borrow<Q>,
+    {
+        Box::new(self.keys[self.range_of(lo, hi)].iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+
+    #[test]
+    fn test() {
+        let mut table = SortedArrayTable::with_capacity(8);
+        for (i, w) in "it was the best of times it was the worst of times"
+            .split(' ')
+            .enumerate()
+        {
+            table.put(w, i);
+        }
+        assert_eq!(table.size(), 7);
+        assert_eq!(
+            table.keys().cloned().collect::<Vec<_>>(),
+            ["best", "it", "of", "the", "times", "was", "worst"]
+        );
+        assert_eq!(table.get("it"), Some(&6));
+        *table.get_mut("it").unwrap() += 1;
+        assert_eq!(table.put("it", 0), Some(7));
+        assert_eq!(table.size_range("c", "t"), 2);
+        assert_eq!(table.size_range("t", "c"), 0);
+        assert_eq!(table.remove("of"), Some(("of", 10)));
+        assert_eq!(table.remove("of"), None);
+        assert_eq!(table.pop_min(), Some(("best", 3)));
+        assert_eq!(table.pop_max(), Some(("worst", 9)));
+        assert_eq!(
+            format!("{:?}", table),
+            r#"{"it": 0, "the": 8, "times": 11, "was": 7}"#
+        );
+        while table.pop_min().is_some() {}
+        assert!(table.is_empty());
+        assert_eq!(table.pop_max(), None);
+    }
+}
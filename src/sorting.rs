@@ -1,7 +1,16 @@
-use rand::seq::SliceRandom;
+#[cfg(feature = "std")]
 use rand::thread_rng;
+use rand::Rng;
+
+use crate::network;
+use crate::random;
+use crate::total_order::{self, Incomparable};
 
 /// Implementation of sorting algorithms
+///
+/// Every pair of keys must be comparable. Keys such as `f64::NAN` leave
+/// the output in an unspecified order. Use the `checked_*` variants or
+/// `total_order::sort_floats` for such keys.
 pub trait Sorting
 where
     Self: PartialOrd + Clone,
@@ -12,19 +21,7 @@ where
     /// are greater than the current key. Else insert the current key
     /// to the vacancy.
     fn insertion_sort(keys: &mut [Self]) {
-        let f = PartialOrd::gt;
-
-        for j in 1..keys.len() {
-            let key = keys[j].clone();
-            let mut i = j;
-            while i > 0 && f(&keys[i - 1], &key) {
-                // move value of keys[i-1] one place right
-                keys[i] = keys[i - 1].clone();
-                i -= 1;
-            }
-            // insertion happens here
-            keys[i] = key;
-        }
+        insertion_sort_by(keys, PartialOrd::lt)
     }
 
     /// Select the smallest item in the unsorted portion of keys
@@ -32,101 +29,90 @@ where
     /// Now this first item belongs to the sorted portion.
     /// Repeat until no unsorted portion left.
     fn selection_sort(keys: &mut [Self]) {
-        for i in 0..keys.len() {
-            let mut min = i;
-            for j in (i + 1)..keys.len() {
-                if keys[j] < keys[min] {
-                    min = j;
-                }
-            }
-            let temp = keys[i].clone();
-            keys[i] = keys[min].clone();
-            keys[min] = temp;
-        }
+        selection_sort_by(keys, PartialOrd::lt)
     }
 
     /// starting from first item, switch keys[i] keys[i+1] if keys[i] is bigger.
     /// This way, each iteration moves the biggest item to the right most position.
     fn bubble_sort(keys: &mut [Self]) {
-        for i in 0..keys.len() {
-            for j in 1..(keys.len() - i) {
-                if keys[j] < keys[j-1] {
-                    Sorting::swap(keys, j, j-1)
-                }
-            }
-        }
+        bubble_sort_by(keys, PartialOrd::lt)
     }
 
     /// <https://en.wikipedia.org/wiki/Shellsort>
     fn shellsort(keys: &mut [Self]) {
-        let mut h = 1;
-        while h < keys.len() / 3 {
-            h = 3 * h + 1
-        }
-
-        while h >= 1 {
-            for i in h..keys.len() {
-                let key = keys[i].clone();
-                let mut j = i;
-                while j >= h && keys[j - h] > key {
-                    keys[j] = keys[j - h].clone();
-                    j -= h;
-                }
-                keys[j] = key;
-            }
-
-            h /= 3;
-        }
+        shellsort_by(keys, PartialOrd::lt)
     }
 
     /// <https://en.wikipedia.org/wiki/Quicksort>
+    #[cfg(feature = "std")]
     fn quicksort(keys: &mut [Self]) {
-        if keys.len() == 0 {
-            return;
-        }
-        // random shuffle keys
-        let mut rng = thread_rng();
-        keys.shuffle(&mut rng);
-        // recursive sort fn
-        sort(keys, 0, keys.len() - 1);
-        fn sort<T: PartialOrd + Clone>(keys: &mut [T], lo: usize, hi: usize) {
-            if hi <= lo {
-                return;
-            }
-            let j = partition(keys, lo, hi);
-            sort(keys, lo, j - 1);
-            sort(keys, j + 1, hi);
-        }
-        // index lo to hi, keys[lo] is the pivot
-        fn partition<T: PartialOrd + Clone>(keys: &mut [T], lo: usize, hi: usize) -> usize {
-            let mut i = lo;
-            let mut j = hi + 1;
-            let pivot = keys[lo].clone();
-            loop {
-                loop {
-                    i += 1;
-                    if i == hi || keys[i] >= pivot {
-                        break;
-                    }
-                }
-                loop {
-                    j -= 1;
-                    if j == lo || pivot >= keys[j] {
-                        break;
-                    }
-                }
-                if i >= j {
-                    break;
-                }
-                Sorting::swap(keys, i, j);
-            }
-            Sorting::swap(keys, j, lo);
-            j
-        }
+        quicksort_by(keys, PartialOrd::lt, &mut thread_rng())
     }
 
+    /// `quicksort`, shuffling the keys with `rng`. Needs no `std`.
+    /// ```
+    /// # use algorithm_exercise::*;
+    /// let mut keys = [3, 1, 2];
+    /// Sorting::quicksort_with(&mut keys, &mut random::seeded(0));
+    /// assert_eq!(keys, [1, 2, 3]);
+    /// ```
+    fn quicksort_with<R: Rng + ?Sized>(keys: &mut [Self], rng: &mut R) {
+        quicksort_by(keys, PartialOrd::lt, rng)
+    }
 
+    /// Sort a fixed-size array of up to 16 keys with an optimal sorting
+    /// network. Exchanges are done without branching on comparisons.
+    /// ```
+    /// # use algorithm_exercise::*;
+    /// let mut keys = [(2, 'b'), (1, 'a'), (3, 'c'), (0, 'z')];
+    /// Sorting::network_sort(&mut keys);
+    /// assert_eq!(keys, [(0, 'z'), (1, 'a'), (2, 'b'), (3, 'c')]);
+    /// ```
+    fn network_sort<const N: usize>(keys: &mut [Self; N])
+    where
+        Self: Copy,
+    {
+        network::sort(keys)
+    }
+
+    /// `insertion_sort`, failing with the positions of two keys
+    /// that can't be compared instead of sorting them.
+    fn checked_insertion_sort(keys: &mut [Self]) -> Result<(), Incomparable> {
+        total_order::checked(keys, Sorting::insertion_sort)
+    }
 
+    /// `selection_sort`, failing with the positions of two keys
+    /// that can't be compared instead of sorting them.
+    fn checked_selection_sort(keys: &mut [Self]) -> Result<(), Incomparable> {
+        total_order::checked(keys, Sorting::selection_sort)
+    }
+
+    /// `bubble_sort`, failing with the positions of two keys
+    /// that can't be compared instead of sorting them.
+    fn checked_bubble_sort(keys: &mut [Self]) -> Result<(), Incomparable> {
+        total_order::checked(keys, Sorting::bubble_sort)
+    }
+
+    /// `shellsort`, failing with the positions of two keys
+    /// that can't be compared instead of sorting them.
+    fn checked_shellsort(keys: &mut [Self]) -> Result<(), Incomparable> {
+        total_order::checked(keys, Sorting::shellsort)
+    }
+
+    /// `quicksort`, failing with the positions of two keys
+    /// that can't be compared instead of sorting them.
+    /// ```
+    /// # use algorithm_exercise::*;
+    /// let mut keys = [2.0, f64::NAN, 1.0];
+    /// assert!(Sorting::checked_quicksort(&mut keys).is_err());
+    /// let mut keys = [2.0, 3.0, 1.0];
+    /// assert_eq!(Sorting::checked_quicksort(&mut keys), Ok(()));
+    /// assert_eq!(keys, [1.0, 2.0, 3.0]);
+    /// ```
+    #[cfg(feature = "std")]
+    fn checked_quicksort(keys: &mut [Self]) -> Result<(), Incomparable> {
+        total_order::checked(keys, Sorting::quicksort)
+    }
 
     #[doc(hidden)]
     fn swap(keys: &mut [Self], i: usize, j: usize) {
@@ -138,9 +124,141 @@ where
 
 impl<T: PartialOrd + Clone> Sorting for T {}
 
+// The algorithms proper, ordering keys by `is_less` so that they can be
+// shared by `Sorting` and the comparator based `SortExt::*_by` methods.
+
+pub(crate) fn insertion_sort_by<T, F>(keys: &mut [T], mut is_less: F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> bool,
+{
+    for j in 1..keys.len() {
+        let key = keys[j].clone();
+        let mut i = j;
+        while i > 0 && is_less(&key, &keys[i - 1]) {
+            // move value of keys[i-1] one place right
+            keys[i] = keys[i - 1].clone();
+            i -= 1;
+        }
+        // insertion happens here
+        keys[i] = key;
+    }
+}
+
+pub(crate) fn selection_sort_by<T, F>(keys: &mut [T], mut is_less: F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    for i in 0..keys.len() {
+        let mut min = i;
+        for j in (i + 1)..keys.len() {
+            if is_less(&keys[j], &keys[min]) {
+                min = j;
+            }
+        }
+        keys.swap(i, min);
+    }
+}
+
+pub(crate) fn bubble_sort_by<T, F>(keys: &mut [T], mut is_less: F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    for i in 0..keys.len() {
+        for j in 1..(keys.len() - i) {
+            if is_less(&keys[j], &keys[j - 1]) {
+                keys.swap(j, j - 1)
+            }
+        }
+    }
+}
+
+pub(crate) fn shellsort_by<T, F>(keys: &mut [T], mut is_less: F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> bool,
+{
+    let mut h = 1;
+    while h < keys.len() / 3 {
+        h = 3 * h + 1
+    }
+
+    while h >= 1 {
+        for i in h..keys.len() {
+            let key = keys[i].clone();
+            let mut j = i;
+            while j >= h && is_less(&key, &keys[j - h]) {
+                keys[j] = keys[j - h].clone();
+                j -= h;
+            }
+            keys[j] = key;
+        }
+
+        h /= 3;
+    }
+}
+
+pub(crate) fn quicksort_by<T, F, R>(keys: &mut [T], mut is_less: F, rng: &mut R)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> bool,
+    R: Rng + ?Sized,
+{
+    if keys.is_empty() {
+        return;
+    }
+    // random shuffle keys
+    random::shuffle(keys, rng);
+    // recursive sort fn
+    sort(keys, 0, keys.len() - 1, &mut is_less);
+
+    fn sort<T: Clone, F: FnMut(&T, &T) -> bool>(keys: &mut [T], lo: usize, hi: usize, is_less: &mut F) {
+        if hi <= lo {
+            return;
+        }
+        if hi - lo < network::MAX_LEN {
+            network::sort_by(&mut keys[lo..=hi], is_less);
+            return;
+        }
+        let j = partition(keys, lo, hi, is_less);
+        if j > lo {
+            sort(keys, lo, j - 1, is_less);
+        }
+        sort(keys, j + 1, hi, is_less);
+    }
+    // index lo to hi, keys[lo] is the pivot
+    fn partition<T: Clone, F: FnMut(&T, &T) -> bool>(keys: &mut [T], lo: usize, hi: usize, is_less: &mut F) -> usize {
+        let mut i = lo;
+        let mut j = hi + 1;
+        let pivot = keys[lo].clone();
+        loop {
+            loop {
+                i += 1;
+                if i == hi || !is_less(&keys[i], &pivot) {
+                    break;
+                }
+            }
+            loop {
+                j -= 1;
+                if j == lo || !is_less(&pivot, &keys[j]) {
+                    break;
+                }
+            }
+            if i >= j {
+                break;
+            }
+            keys.swap(i, j);
+        }
+        keys.swap(j, lo);
+        j
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::vec;
+    use alloc::vec::Vec;
 
     #[test]
     fn test() {
@@ -152,13 +270,47 @@ mod tests {
             Sorting::insertion_sort,
             Sorting::selection_sort,
             Sorting::shellsort,
-            Sorting::quicksort,
+            |keys| Sorting::quicksort_with(keys, &mut random::seeded(3)),
             Sorting::bubble_sort
         ];
+        #[cfg(feature = "std")]
+        let sortings = [sortings, vec![Sorting::quicksort]].concat();
         for sort in sortings {
             let mut keys = keys_original.clone();
             sort(&mut keys);
             assert_eq!(sorted, keys);
         }
+
+        let keys_original: Vec<u32> = (0..1000).map(|i| i * 7919 % 1009).collect();
+        let mut sorted = keys_original.clone();
+        sorted.sort_unstable();
+        #[cfg(feature = "std")]
+        {
+            let mut keys = keys_original.clone();
+            Sorting::quicksort(&mut keys);
+            assert_eq!(sorted, keys);
+        }
+        let mut keys = keys_original.clone();
+        Sorting::quicksort_with(&mut keys, &mut random::seeded(7));
+        assert_eq!(sorted, keys);
+
+        // NaN leaves the order unspecified, but mustn't panic in debug builds
+        let keys_original: Vec<f64> = (0..100)
+            .map(|i| if i % 3 == 0 { f64::NAN } else { (i * 37 % 101) as f64 })
+            .collect();
+        let sortings: Vec<fn(&mut [f64])> = vec![
+            Sorting::insertion_sort,
+            Sorting::selection_sort,
+            Sorting::shellsort,
+            Sorting::bubble_sort,
+            |keys| Sorting::quicksort_with(keys, &mut random::seeded(1)),
+        ];
+        #[cfg(feature = "std")]
+        let sortings = [sortings, vec![Sorting::quicksort]].concat();
+        for sort in sortings {
+            let mut keys = keys_original.clone();
+            sort(&mut keys);
+            assert_eq!(keys.iter().filter(|k| k.is_nan()).count(), 34);
+        }
     }
 }
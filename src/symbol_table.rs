@@ -0,0 +1,209 @@
+//! The ordered symbol table API shared by the search trees, so code can
+//! be written once against any of them.
+use alloc::boxed::Box;
+use core::borrow::Borrow;
+
+/// Ordered symbol table: a map whose keys can be searched by order as
+/// well as by equality. Lookups take any borrowed form of the key.
+/// ```
+/// # use algorithm_exercise::*;
+/// fn second<S: OrderedSymbolTable<u32, &'static str>>(mut table: S) -> Option<u32> {
+///     for (i, name) in ["zero", "one", "two"].iter().enumerate() {
+///         table.put(i as u32, name);
+///     }
+///     table.select(1).cloned()
+/// }
+/// assert_eq!(second(BinarySearchTree::new()), Some(1));
+/// assert_eq!(second(BalancedSearchTree::new()), Some(1));
+/// assert_eq!(second(SortedArrayTable::new()), Some(1));
+/// ```
+pub trait OrderedSymbolTable<K: Ord, V> {
+    /// Insert `value` under `key`, returning the value it replaces.
+    fn put(&mut self, key: K, value: V) -> Option<V>;
+
+    /// The value under `key`.
+    fn get<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>;
+
+    /// Remove `key` and its value, if present.
+    fn delete<Q: Ord + ?Sized>(&mut self, key: &Q)
+    where
+        K: Borrow<Q>;
+
+    /// Whether there is a value under `key`.
+    fn contains<Q: Ord + ?Sized>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.get(key).is_some()
+    }
+
+    /// Number of keys.
+    fn size(&self) -> usize;
+
+    /// Whether there are no keys.
+    fn is_empty(&self) -> bool {
+        self.size() == 0
+    }
+
+    /// The smallest key.
+    fn min(&self) -> Option<&K>;
+
+    /// The largest key.
+    fn max(&self) -> Option<&K>;
+
+    /// The largest key less than or equal to `key`.
+    fn floor<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&K>
+    where
+        K: Borrow<Q>;
+
+    /// The smallest key greater than or equal to `key`.
+    fn ceiling<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&K>
+    where
+        K: Borrow<Q>;
+
+    /// Number of keys less than `key`.
+    fn rank<Q: Ord + ?Sized>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q>;
+
+    /// The key with `rank` smaller keys.
+    fn select(&self, rank: usize) -> Option<&K>;
+
+    /// The keys from `lo` to `hi`, both included, in order.
+    fn keys<'a, Q: Ord + ?Sized>(&'a self, lo: &Q, hi: &Q) -> Box<dyn Iterator<Item = &'a K> + 'a>
+    where
+        K: Borrow<Q>;
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::random;
+    use crate::{BalancedSearchTree, BinarySearchTree, SortedArrayTable};
+    use alloc::collections::BTreeMap;
+    use alloc::vec::Vec;
+    use core::ops::Bound;
+    use rand::Rng;
+
+    fn assert_matches<S: OrderedSymbolTable<u32, u32>>(table: &S, model: &BTreeMap<u32, u32>) {
+        let keys: Vec<u32> = model.keys().cloned().collect();
+        assert_eq!(table.size(), model.len());
+        assert_eq!(table.is_empty(), model.is_empty());
+        assert_eq!(table.min(), keys.first());
+        assert_eq!(table.max(), keys.last());
+        for k in 0..=101 {
+            assert_eq!(table.get(&k), model.get(&k));
+            assert_eq!(table.contains(&k), model.contains_key(&k));
+            assert_eq!(
+                table.floor(&k),
+                model.range(..=k).next_back().map(|(k, _)| k)
+            );
+            assert_eq!(table.ceiling(&k), model.range(k..).next().map(|(k, _)| k));
+            assert_eq!(table.rank(&k), model.range(..k).count());
+        }
+        for (i, k) in keys.iter().enumerate() {
+            assert_eq!(table.select(i), Some(k));
+            assert_eq!(table.rank(k), i);
+        }
+        assert_eq!(table.select(keys.len()), None);
+        for &(lo, hi) in &[(0, 101), (10, 20), (33, 33), (50, 40)] {
+            let got: Vec<&u32> = table.keys(&lo, &hi).collect();
+            let want: Vec<&u32> = model
+                .range((Bound::Included(lo), Bound::Included(hi.max(lo))))
+                .filter(|&(&k, _)| k <= hi)
+                .map(|(k, _)| k)
+                .collect();
+            assert_eq!(got, want, "keys({}, {})", lo, hi);
+        }
+    }
+
+    /// The changes a table can be checked for by `random_changes`, each
+    /// returning what it replaced or removed.
+    pub(crate) trait Changes {
+        fn put(&mut self, key: u32, value: u32) -> Option<u32>;
+        fn pop_min(&mut self) -> Option<(u32, u32)>;
+        fn pop_max(&mut self) -> Option<(u32, u32)>;
+        fn remove(&mut self, key: &u32) -> Option<(u32, u32)>;
+    }
+
+    macro_rules! changes {
+        ($($table:ident),*) => {$(
+            impl Changes for $table<u32, u32> {
+                fn put(&mut self, key: u32, value: u32) -> Option<u32> {
+                    $table::put(self, key, value)
+                }
+                fn pop_min(&mut self) -> Option<(u32, u32)> {
+                    $table::pop_min(self)
+                }
+                fn pop_max(&mut self) -> Option<(u32, u32)> {
+                    $table::pop_max(self)
+                }
+                fn remove(&mut self, key: &u32) -> Option<(u32, u32)> {
+                    $table::remove(self, key)
+                }
+            }
+        )*};
+    }
+
+    changes!(BinarySearchTree, BalancedSearchTree, SortedArrayTable);
+
+    /// Make `rounds` random puts, pops and removals of keys below `keys`
+    /// on `table`, checking what each returns against a `BTreeMap`. After
+    /// each change `check` gets the table and the map, for the rest. Returns
+    /// the map.
+    pub(crate) fn random_changes<S, F>(
+        table: &mut S,
+        seed: u64,
+        rounds: u32,
+        keys: u32,
+        mut check: F,
+    ) -> BTreeMap<u32, u32>
+    where
+        S: Changes,
+        F: FnMut(&mut S, &BTreeMap<u32, u32>),
+    {
+        let mut rng = random::seeded(seed);
+        let mut model = BTreeMap::new();
+        for round in 0..rounds {
+            let k = rng.gen_range(0, keys);
+            match rng.gen_range(0, 8) {
+                0 => assert_eq!(table.pop_min(), model.pop_first()),
+                1 => assert_eq!(table.pop_max(), model.pop_last()),
+                2..=4 => assert_eq!(table.remove(&k), model.remove_entry(&k)),
+                _ => assert_eq!(table.put(k, round), model.insert(k, round)),
+            }
+            check(table, &model);
+        }
+        model
+    }
+
+    /// The conformance suite every `OrderedSymbolTable` must pass.
+    fn conformance<S: OrderedSymbolTable<u32, u32> + Changes + Default>() {
+        let mut table = S::default();
+        assert_matches(&table, &BTreeMap::new());
+        table.delete(&3);
+        assert!(table.is_empty());
+
+        let mut round = 0;
+        let model = random_changes(&mut table, 44, 2000, 100, |table, model| {
+            if round % 100 == 0 {
+                assert_matches(table, model);
+            }
+            round += 1;
+        });
+        assert_matches(&table, &model);
+        while let Some(&k) = table.min() {
+            table.delete(&k);
+        }
+        assert_matches(&table, &BTreeMap::new());
+    }
+
+    #[test]
+    fn test() {
+        conformance::<BinarySearchTree<u32, u32>>();
+        conformance::<BalancedSearchTree<u32, u32>>();
+        conformance::<SortedArrayTable<u32, u32>>();
+    }
+}
@@ -0,0 +1,227 @@
+//! Sorting keys that `PartialOrd` can't always compare.
+//!
+//! The algorithms in `Sorting` assume every pair of keys is comparable.
+//! The checked variants report the first pair that isn't, and the float
+//! helpers here give `f32`/`f64` a total order so they can be sorted safely.
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::Cell;
+use core::cmp::Ordering;
+use core::fmt;
+
+use crate::verify;
+
+/// Two keys, at positions `i` and `j` of the input, for which
+/// `partial_cmp` returned `None`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Incomparable {
+    pub i: usize,
+    pub j: usize,
+}
+
+impl fmt::Display for Incomparable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "keys at {} and {} are not comparable", self.i, self.j)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Incomparable {}
+
+/// A key in a checked sort, remembering its input position and
+/// sharing a record of the first failed comparison.
+#[derive(Clone)]
+pub(crate) struct Checked<T> {
+    key: T,
+    position: usize,
+    failed: Rc<Cell<Option<Incomparable>>>,
+}
+
+impl<T: PartialOrd> PartialEq for Checked<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl<T: PartialOrd> PartialOrd for Checked<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        // once a comparison failed the outcome is thrown away, so let the
+        // algorithm run out as quickly as possible
+        if self.failed.get().is_some() {
+            return Some(Ordering::Equal);
+        }
+        match self.key.partial_cmp(&other.key) {
+            Some(ordering) => Some(ordering),
+            None => {
+                self.failed.set(Some(Incomparable {
+                    i: self.position,
+                    j: other.position,
+                }));
+                Some(Ordering::Equal)
+            }
+        }
+    }
+}
+
+/// Run `alg` on `keys`, failing instead of sorting if it ever has to
+/// compare two keys that aren't comparable. On failure `keys` is left
+/// untouched.
+pub(crate) fn checked<T, F>(keys: &mut [T], alg: F) -> Result<(), Incomparable>
+where
+    T: PartialOrd + Clone,
+    F: FnOnce(&mut [Checked<T>]),
+{
+    let failed = Rc::new(Cell::new(None));
+    let mut wrapped: Vec<Checked<T>> = keys
+        .iter()
+        .cloned()
+        .enumerate()
+        .map(|(position, key)| Checked {
+            key,
+            position,
+            failed: failed.clone(),
+        })
+        .collect();
+    alg(&mut wrapped);
+    if let Some(e) = failed.get() {
+        return Err(e);
+    }
+    for (key, w) in keys.iter_mut().zip(wrapped) {
+        *key = w.key;
+    }
+    // every comparison succeeded, so the output must be sorted
+    debug_assert!(verify::is_sorted(keys));
+    Ok(())
+}
+
+/// Where `NaN`s go in a total-order float sort.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Nans {
+    First,
+    Last,
+}
+
+/// Floating point types that can be given a total order.
+pub trait Float: Copy + PartialOrd + private::Sealed {
+    #[doc(hidden)]
+    fn is_nan(self) -> bool;
+    #[doc(hidden)]
+    fn total_cmp(&self, other: &Self) -> Ordering;
+}
+
+impl Float for f32 {
+    fn is_nan(self) -> bool {
+        f32::is_nan(self)
+    }
+    fn total_cmp(&self, other: &Self) -> Ordering {
+        f32::total_cmp(self, other)
+    }
+}
+
+impl Float for f64 {
+    fn is_nan(self) -> bool {
+        f64::is_nan(self)
+    }
+    fn total_cmp(&self, other: &Self) -> Ordering {
+        f64::total_cmp(self, other)
+    }
+}
+
+mod private {
+    pub trait Sealed {}
+    impl Sealed for f32 {}
+    impl Sealed for f64 {}
+}
+
+/// A float ordered totally: `NaN`s (all equal to each other) first or
+/// last as chosen, and `-0.0` before `+0.0`.
+#[derive(Clone, Copy, Debug)]
+pub struct TotalOrder<F: Float> {
+    pub value: F,
+    pub nans: Nans,
+}
+
+impl<F: Float> PartialEq for TotalOrder<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl<F: Float> PartialOrd for TotalOrder<F> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let nan_first = self.nans == Nans::First;
+        Some(match (self.value.is_nan(), other.value.is_nan()) {
+            (true, true) => Ordering::Equal,
+            (true, false) if nan_first => Ordering::Less,
+            (true, false) => Ordering::Greater,
+            (false, true) if nan_first => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) => self.value.total_cmp(&other.value),
+        })
+    }
+}
+
+/// Sort floats in total order with any algorithm of `Sorting`.
+/// ```
+/// # use algorithm_exercise::*;
+/// # use algorithm_exercise::total_order::{sort_floats, Nans};
+/// let mut keys = [1.0, f64::NAN, 0.0, -0.0, -1.0];
+/// sort_floats(&mut keys, Nans::Last, Sorting::shellsort);
+/// assert_eq!(keys[..4], [-1.0, -0.0, 0.0, 1.0]);
+/// assert!(keys[1].is_sign_negative() && keys[2].is_sign_positive());
+/// assert!(keys[4].is_nan());
+/// ```
+pub fn sort_floats<F, A>(keys: &mut [F], nans: Nans, alg: A)
+where
+    F: Float,
+    A: FnOnce(&mut [TotalOrder<F>]),
+{
+    let mut wrapped: Vec<TotalOrder<F>> = keys
+        .iter()
+        .map(|&value| TotalOrder { value, nans })
+        .collect();
+    alg(&mut wrapped);
+    for (key, w) in keys.iter_mut().zip(wrapped) {
+        *key = w.value;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{random, Sorting};
+    use alloc::vec;
+
+    #[test]
+    fn test() {
+        let keys = [3.0, 1.0, f64::NAN, 2.0, -0.0, 0.0];
+
+        let mut sorted = keys;
+        assert_eq!(Sorting::checked_shellsort(&mut sorted[..2]), Ok(()));
+        assert_eq!(sorted[..2], [1.0, 3.0]);
+        let e = Sorting::checked_insertion_sort(&mut sorted).unwrap_err();
+        assert!(e.i == 2 || e.j == 2);
+        assert!(sorted[2].is_nan());
+        assert_eq!(sorted[3..], keys[3..]);
+        #[cfg(feature = "std")]
+        assert!(Sorting::checked_quicksort(&mut sorted).is_err());
+
+        let sortings: Vec<fn(&mut [TotalOrder<f64>])> = vec![
+            Sorting::insertion_sort,
+            Sorting::selection_sort,
+            Sorting::shellsort,
+            |keys| Sorting::quicksort_with(keys, &mut random::seeded(2)),
+            Sorting::bubble_sort,
+        ];
+        for sort in sortings {
+            let mut sorted = keys;
+            sort_floats(&mut sorted, Nans::First, sort);
+            assert!(sorted[0].is_nan());
+            assert_eq!(sorted[1..], [-0.0, 0.0, 1.0, 2.0, 3.0]);
+            assert!(sorted[1].is_sign_negative());
+            sort_floats(&mut sorted, Nans::Last, sort);
+            assert!(sorted[5].is_nan());
+            assert!(sorted[0].is_sign_negative());
+        }
+    }
+}
@@ -0,0 +1,395 @@
+//! Walks shared by the search trees, written against `TreeNode`, the
+//! little they need to read a node.
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::ops::{Bound, RangeBounds};
+
+use crate::invariants::Violation;
+
+/// Read access to a node of a binary search tree that counts the nodes
+/// of each subtree.
+pub(crate) trait TreeNode {
+    type Key;
+    type Value;
+
+    fn key(&self) -> &Self::Key;
+    fn value(&self) -> &Self::Value;
+    fn left(&self) -> Option<&Self>;
+    fn right(&self) -> Option<&Self>;
+    /// Number of nodes in the subtree rooted here.
+    fn size(&self) -> usize;
+}
+
+/// Whether `key` is not below the `start` bound of a range.
+fn above<Q: Ord + ?Sized>(start: Bound<&Q>, key: &Q) -> bool {
+    match start {
+        Bound::Included(lo) => key >= lo,
+        Bound::Excluded(lo) => key > lo,
+        Bound::Unbounded => true,
+    }
+}
+
+/// Whether `key` is not above the `end` bound of a range.
+fn below<Q: Ord + ?Sized>(end: Bound<&Q>, key: &Q) -> bool {
+    match end {
+        Bound::Included(hi) => key <= hi,
+        Bound::Excluded(hi) => key < hi,
+        Bound::Unbounded => true,
+    }
+}
+
+/// In-order iterator over the key value pairs of a range of a tree,
+/// from either end.
+pub(crate) struct Iter<'a, N> {
+    /// Nodes to yield from the front, each before its right subtree.
+    front: Vec<&'a N>,
+    /// Nodes to yield from the back, each before its left subtree.
+    back: Vec<&'a N>,
+    /// Number of pairs left in range.
+    len: usize,
+}
+
+impl<'a, N: TreeNode> Iter<'a, N> {
+    /// The pairs below `root` with keys in `range`. The descents to either
+    /// end of the range also count the keys outside it, so the length is
+    /// known without looking the bounds up again.
+    pub fn new<Q, R>(root: Option<&'a N>, range: R) -> Self
+    where
+        N::Key: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let mut iter = Iter {
+            front: vec![],
+            back: vec![],
+            len: 0,
+        };
+        let before = Self::push_path(
+            root,
+            false,
+            |key| above(range.start_bound(), key.borrow()),
+            &mut iter.front,
+        );
+        let after = Self::push_path(
+            root,
+            true,
+            |key| below(range.end_bound(), key.borrow()),
+            &mut iter.back,
+        );
+        // the two overlap when the range is empty
+        let size = root.map_or(0, N::size);
+        iter.len = size.saturating_sub(before + after);
+        iter
+    }
+
+    /// Push the nodes `inside` the range on the way from `node` to the
+    /// first key inside (or the last one, going `right`) onto `path`.
+    /// Returns the number of keys passed over outside the range.
+    fn push_path<F>(mut node: Option<&'a N>, right: bool, inside: F, path: &mut Vec<&'a N>) -> usize
+    where
+        F: Fn(&N::Key) -> bool,
+    {
+        let mut outside = 0;
+        while let Some(current) = node {
+            let is_inside = inside(current.key());
+            if is_inside {
+                path.push(current);
+            }
+            let (near, far) = if right {
+                (current.right(), current.left())
+            } else {
+                (current.left(), current.right())
+            };
+            node = if is_inside {
+                near
+            } else {
+                outside += near.map_or(0, N::size) + 1;
+                far
+            };
+        }
+        outside
+    }
+
+    fn pop(path: &mut Vec<&'a N>, right: bool) -> Option<(&'a N::Key, &'a N::Value)> {
+        let node = path.pop()?;
+        let child = if right { node.left() } else { node.right() };
+        Self::push_path(child, right, |_| true, path);
+        Some((node.key(), node.value()))
+    }
+}
+
+impl<'a, N: TreeNode> Iterator for Iter<'a, N> {
+    type Item = (&'a N::Key, &'a N::Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        Self::pop(&mut self.front, false)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, N: TreeNode> DoubleEndedIterator for Iter<'a, N> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        Self::pop(&mut self.back, true)
+    }
+}
+
+impl<N: TreeNode> ExactSizeIterator for Iter<'_, N> {}
+
+/// The keys of an iterator over key value pairs.
+pub struct Keys<I>(pub(crate) I);
+
+impl<'a, K: 'a, V: 'a, I: Iterator<Item = (&'a K, &'a V)>> Iterator for Keys<I> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<&'a K> {
+        self.0.next().map(|(key, _)| key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, K: 'a, V: 'a, I> DoubleEndedIterator for Keys<I>
+where
+    I: DoubleEndedIterator<Item = (&'a K, &'a V)>,
+{
+    fn next_back(&mut self) -> Option<&'a K> {
+        self.0.next_back().map(|(key, _)| key)
+    }
+}
+
+impl<'a, K: 'a, V: 'a, I> ExactSizeIterator for Keys<I> where
+    I: ExactSizeIterator<Item = (&'a K, &'a V)>
+{
+}
+
+/// The values of an iterator over key value pairs.
+pub struct Values<I>(pub(crate) I);
+
+impl<'a, K: 'a, V: 'a, I: Iterator<Item = (&'a K, &'a V)>> Iterator for Values<I> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<&'a V> {
+        self.0.next().map(|(_, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, K: 'a, V: 'a, I> DoubleEndedIterator for Values<I>
+where
+    I: DoubleEndedIterator<Item = (&'a K, &'a V)>,
+{
+    fn next_back(&mut self) -> Option<&'a V> {
+        self.0.next_back().map(|(_, value)| value)
+    }
+}
+
+impl<'a, K: 'a, V: 'a, I> ExactSizeIterator for Values<I> where
+    I: ExactSizeIterator<Item = (&'a K, &'a V)>
+{
+}
+
+/// Call `f` on every node below `root` with its depth, in pre-order. The
+/// pending subtrees are kept on a stack rather than recursed into.
+pub(crate) fn walk_depths<'a, N: TreeNode, F: FnMut(&'a N, usize)>(root: Option<&'a N>, mut f: F) {
+    let mut stack: Vec<(&N, usize)> = root.map(|root| (root, 0)).into_iter().collect();
+    while let Some((node, depth)) = stack.pop() {
+        f(node, depth);
+        stack.extend(node.right().map(|right| (right, depth + 1)));
+        stack.extend(node.left().map(|left| (left, depth + 1)));
+    }
+}
+
+/// Number of nodes at each depth below `root`, from the root down.
+pub(crate) fn depth_histogram<N: TreeNode>(root: Option<&N>) -> Vec<usize> {
+    let mut histogram = vec![];
+    walk_depths(root, |_, depth| {
+        if depth == histogram.len() {
+            histogram.push(0);
+        }
+        histogram[depth] += 1;
+    });
+    histogram
+}
+
+/// Number of levels below `root`; 0 for an empty tree.
+pub(crate) fn height<N: TreeNode>(root: Option<&N>) -> usize {
+    depth_histogram(root).len()
+}
+
+/// The internal path length below `root` divided by the number of
+/// nodes; 0 for an empty tree.
+pub(crate) fn average_depth<N: TreeNode>(root: Option<&N>) -> f64 {
+    let path_length: usize = depth_histogram(root)
+        .iter()
+        .enumerate()
+        .map(|(depth, count)| depth * count)
+        .sum();
+    match root.map_or(0, N::size) {
+        0 => 0.0,
+        size => path_length as f64 / size as f64,
+    }
+}
+
+/// Number of nodes without children below `root`.
+pub(crate) fn leaf_count<N: TreeNode>(root: Option<&N>) -> usize {
+    let mut leaves = 0;
+    walk_depths(root, |node, _| {
+        if node.left().is_none() && node.right().is_none() {
+            leaves += 1;
+        }
+    });
+    leaves
+}
+
+/// A node on the stack of `check`, waiting for its subtrees.
+struct Pending<'a, N, B> {
+    node: &'a N,
+    /// Position of the smallest key below `node` in key order.
+    first: usize,
+    /// Rank of that key going by the subtree counts.
+    rank: usize,
+    /// Size of the left subtree and what `finish` made of it, once done.
+    left: Option<(usize, B)>,
+}
+
+/// Check the keys below `root` for symmetric order, and the subtree
+/// counts against the subtree sizes and against the positions of the
+/// keys, which is what `rank_of_key` and `key_of_rank` go by. The walk
+/// is post-order, with an explicit stack so that a degenerate tree can't
+/// overflow the call stack.
+///
+/// `finish` checks whatever else a tree keeps: it gets each node with its
+/// position and what it returned for the two subtrees (`B::default()` for
+/// a missing one), and returns that for the node's own subtree.
+pub(crate) fn check<N, B, F>(root: Option<&N>, violations: &mut Vec<Violation>, mut finish: F)
+where
+    N: TreeNode,
+    N::Key: Ord,
+    B: Copy + Default,
+    F: FnMut(&N, usize, B, B, &mut Vec<Violation>) -> B,
+{
+    let mut stack: Vec<Pending<N, B>> = vec![];
+    let mut previous: Option<&N::Key> = None;
+    let mut next = root.map(|root| (root, 0, 0));
+    // size of the subtree finished last, and what `finish` made of it
+    let mut done = (0, B::default());
+    loop {
+        if let Some((node, first, rank)) = next {
+            stack.push(Pending {
+                node,
+                first,
+                rank,
+                left: None,
+            });
+            next = node.left().map(|left| (left, first, rank));
+            done = (0, B::default());
+            continue;
+        }
+        let pending = match stack.last_mut() {
+            Some(pending) => pending,
+            None => return,
+        };
+        let node = pending.node;
+        match pending.left {
+            None => {
+                pending.left = Some(done);
+                let position = pending.first + done.0;
+                if previous.is_some_and(|key| key >= node.key()) {
+                    violations.push(Violation::SymmetricOrder { node: position });
+                }
+                previous = Some(node.key());
+                let rank = pending.rank + node.left().map_or(0, N::size);
+                if rank != position {
+                    violations.push(Violation::Rank {
+                        node: position,
+                        rank,
+                    });
+                }
+                next = node.right().map(|right| (right, position + 1, rank + 1));
+                done = (0, B::default());
+            }
+            Some((left, left_done)) => {
+                let position = pending.first + left;
+                let actual = left + done.0 + 1;
+                if node.size() != actual {
+                    violations.push(Violation::Count {
+                        node: position,
+                        n: node.size(),
+                        actual,
+                    });
+                }
+                done = (
+                    actual,
+                    finish(node, position, left_done, done.1, violations),
+                );
+                stack.pop();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BalancedSearchTree, BinarySearchTree};
+    use alloc::vec::Vec;
+    use core::ops::{Bound, RangeBounds};
+
+    #[test]
+    fn test() {
+        let keys = [1, 3, 4, 6, 7, 9];
+        let mut bst = BinarySearchTree::new();
+        let mut rbt = BalancedSearchTree::new();
+        for &k in &keys {
+            bst.put(k, k * 10);
+            rbt.put(k, k * 10);
+        }
+        let bounds = |k| [Bound::Included(k), Bound::Excluded(k), Bound::Unbounded];
+        for lo in 0..11 {
+            for hi in 0..11 {
+                for &start in &bounds(lo) {
+                    for &end in &bounds(hi) {
+                        let want: Vec<_> = keys
+                            .iter()
+                            .filter(|&k| (start, end).contains(k))
+                            .map(|k| (k, k * 10))
+                            .collect();
+                        let got: Vec<_> = bst.range((start, end)).map(|(k, v)| (k, *v)).collect();
+                        assert_eq!(got, want, "{:?}", (start, end));
+                        assert_eq!(bst.range((start, end)).len(), want.len());
+                        let got: Vec<_> = rbt
+                            .range((start, end))
+                            .rev()
+                            .map(|(k, v)| (k, *v))
+                            .collect();
+                        assert!(got.iter().rev().eq(want.iter()), "{:?}", (start, end));
+                        assert_eq!(rbt.range((start, end)).len(), want.len());
+                    }
+                }
+            }
+        }
+        let mut iter = bst.keys();
+        assert_eq!(iter.len(), 6);
+        assert_eq!((iter.next(), iter.next_back()), (Some(&1), Some(&9)));
+        assert_eq!(
+            rbt.values().rev().cloned().collect::<Vec<_>>(),
+            [90, 70, 60, 40, 30, 10]
+        );
+    }
+}
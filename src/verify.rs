@@ -0,0 +1,178 @@
+//! Post-condition checks for sorting algorithms.
+//!
+//! These are used to certify the algorithms in `Sorting` as well as any
+//! other sort that works on a slice in place.
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::Sorting;
+
+/// Returns `true` if no key in `keys` is smaller than the key before it.
+///
+/// Neighbours that can't be compared (e.g. `NaN`) are not treated as an
+/// inversion.
+/// ```
+/// # use algorithm_exercise::verify;
+/// assert!(verify::is_sorted(&[1, 2, 2, 3]));
+/// assert!(!verify::is_sorted(&[2, 1]));
+/// ```
+pub fn is_sorted<T: PartialOrd>(keys: &[T]) -> bool {
+    keys.windows(2)
+        .all(|w| w[1].partial_cmp(&w[0]) != Some(Ordering::Less))
+}
+
+/// Returns `true` if no key in `keys` compares `Less` than the key before it
+/// according to `compare`.
+/// ```
+/// # use algorithm_exercise::verify;
+/// assert!(verify::is_sorted_by(&[3, 2, 1], |a, b| b.cmp(a)));
+/// ```
+pub fn is_sorted_by<T, F>(keys: &[T], mut compare: F) -> bool
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    keys.windows(2)
+        .all(|w| compare(&w[1], &w[0]) != Ordering::Less)
+}
+
+/// Returns `true` if `result` holds exactly the keys of `original`, counting
+/// duplicates, in any order.
+///
+/// Keys are matched with `==`, so a key that isn't equal to itself (e.g. `NaN`)
+/// never has a match. Keys that are all comparable are matched by sorting
+/// both sides; otherwise, as under a partial order like subset inclusion,
+/// each key is looked for among the others, taking quadratic time.
+/// ```
+/// # use algorithm_exercise::verify;
+/// assert!(verify::is_permutation_of(&[3, 1, 2, 1], &[1, 1, 2, 3]));
+/// assert!(!verify::is_permutation_of(&[3, 1, 2, 1], &[1, 2, 2, 3]));
+/// ```
+pub fn is_permutation_of<T: PartialOrd>(original: &[T], result: &[T]) -> bool {
+    if original.len() != result.len() {
+        return false;
+    }
+    let mut a: Vec<&T> = original.iter().collect();
+    let mut b: Vec<&T> = result.iter().collect();
+    Sorting::shellsort(&mut a);
+    Sorting::shellsort(&mut b);
+    if is_chain(&a) && is_chain(&b) {
+        // sorted into chains, so equal keys sit next to each other
+        return a == b;
+    }
+    let mut matched = vec![false; result.len()];
+    original.iter().all(|key| {
+        let found = (0..result.len()).find(|&i| !matched[i] && result[i] == *key);
+        found.map(|i| matched[i] = true).is_some()
+    })
+}
+
+/// Whether each key in `keys` is comparable to and not less than the one
+/// before it, which makes them all comparable to each other.
+fn is_chain<T: PartialOrd>(keys: &[&T]) -> bool {
+    keys.windows(2)
+        .all(|w| w[0].partial_cmp(w[1]).is_some_and(|o| o != Ordering::Greater))
+}
+
+/// A record tagged with its position in the input of a sort.
+///
+/// Comparisons only look at `value`, so a sort sees tagged records exactly
+/// as it would see the bare ones.
+#[derive(Clone, Debug)]
+pub struct Tagged<T> {
+    pub value: T,
+    pub position: usize,
+}
+
+impl<T: PartialEq> PartialEq for Tagged<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: PartialOrd> PartialOrd for Tagged<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+/// Run `alg` on `records` tagged with their positions and check that the
+/// output is sorted, is a permutation of the input and keeps equal records
+/// in their original order.
+/// ```
+/// # use algorithm_exercise::*;
+/// let records = [2, 1, 2, 1, 3];
+/// assert!(verify::is_stable_sort(Sorting::insertion_sort, &records));
+/// ```
+pub fn is_stable_sort<T, F>(alg: F, records: &[T]) -> bool
+where
+    T: PartialOrd + Clone,
+    F: FnOnce(&mut [Tagged<T>]),
+{
+    let mut tagged: Vec<Tagged<T>> = records
+        .iter()
+        .cloned()
+        .enumerate()
+        .map(|(position, value)| Tagged { value, position })
+        .collect();
+    alg(&mut tagged);
+
+    let mut seen = vec![false; records.len()];
+    for t in &tagged {
+        match seen.get_mut(t.position) {
+            Some(s) if !*s => *s = true,
+            _ => return false,
+        }
+    }
+    is_sorted(&tagged)
+        && tagged
+            .windows(2)
+            .all(|w| w[0] != w[1] || w[0].position < w[1].position)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let keys = vec![1, 0, 1, 0, 2, 1];
+        assert!(is_stable_sort(Sorting::insertion_sort, &keys));
+        assert!(is_stable_sort(Sorting::bubble_sort, &keys));
+        assert!(!is_stable_sort(Sorting::selection_sort, &keys));
+        assert!(!is_stable_sort(|r: &mut [Tagged<i32>]| r.reverse(), &keys));
+        assert!(!is_stable_sort(
+            |r: &mut [Tagged<i32>]| r[0] = r[1].clone(),
+            &keys
+        ));
+
+        let mut sorted = keys.clone();
+        Sorting::shellsort(&mut sorted);
+        assert!(is_sorted(&sorted));
+        assert!(is_permutation_of(&keys, &sorted));
+        assert!(!is_permutation_of(&keys, &sorted[1..]));
+        assert!(!is_sorted(&keys));
+        assert!(!is_sorted_by(&sorted, |a, b| b.cmp(a)));
+
+        // sets as bitmasks, ordered by inclusion
+        #[derive(Debug, PartialEq)]
+        struct Set(u32);
+        impl PartialOrd for Set {
+            fn partial_cmp(&self, other: &Set) -> Option<Ordering> {
+                match (self.0 & !other.0, other.0 & !self.0) {
+                    (0, 0) => Some(Ordering::Equal),
+                    (0, _) => Some(Ordering::Less),
+                    (_, 0) => Some(Ordering::Greater),
+                    _ => None,
+                }
+            }
+        }
+        assert!(is_permutation_of(&[Set(1), Set(2)], &[Set(2), Set(1)]));
+        assert!(is_permutation_of(
+            &[Set(3), Set(1), Set(2), Set(1)],
+            &[Set(1), Set(2), Set(1), Set(3)]
+        ));
+        assert!(!is_permutation_of(&[Set(1), Set(2)], &[Set(2), Set(2)]));
+        assert!(!is_permutation_of(&[f64::NAN], &[f64::NAN]));
+    }
+}